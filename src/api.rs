@@ -1,59 +1,347 @@
-use graphql_client::Response;
+use std::{
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use graphql_client::{GraphQLQuery, Response};
+use reqwest::{header::RETRY_AFTER, StatusCode};
 use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use tokio::sync::Semaphore;
+
+use crate::{constant, credential::Credentials, error::Error};
+
+pub use self::mock::MockApi;
+
+mod mock;
+
+/// Requests in flight at once across every client, unless `--concurrency` says otherwise.
+pub const DEFAULT_CONCURRENCY: usize = 4;
 
-use crate::credential::Credentials;
+static RETRY: OnceLock<RetryPolicy> = OnceLock::new();
+static CONCURRENCY: OnceLock<Semaphore> = OnceLock::new();
 
 #[derive(Debug, thiserror::Error)]
 pub enum ApiError {
     #[error(transparent)]
     ReqwestError(reqwest::Error),
     #[error(transparent)]
-    CredentialsError(anyhow::Error),
+    CredentialsError(#[from] Error),
+    #[error("{}", .0.iter().map(|e| e.message.as_str()).collect::<Vec<_>>().join(", "))]
+    Graphql(Vec<GraphqlError>),
+    #[error("The API returned an empty response.")]
+    EmptyResponse,
+    #[error("Unexpected API response: {0}")]
+    InvalidResponse(#[from] serde_json::Error),
+}
+
+/// An error reported in a GraphQL response, with the machine-readable `code` the API sets in
+/// its extensions (e.g. `NOT_FOUND`).
+#[derive(Debug, Clone)]
+pub struct GraphqlError {
+    pub message: String,
+    pub code: Option<String>,
+}
+
+impl From<graphql_client::Error> for GraphqlError {
+    fn from(error: graphql_client::Error) -> Self {
+        let code = error
+            .extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get("code"))
+            .and_then(|code| code.as_str())
+            .map(str::to_string);
+
+        Self {
+            message: error.message,
+            code,
+        }
+    }
+}
+
+/// How often transient failures (5xx responses, timeouts, dropped connections) are retried.
+/// Delays double from [`RetryPolicy::BASE_DELAY`] up to `max_delay`, with random jitter so
+/// parallel scripts don't retry in lockstep.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub retries: u32,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub const BASE_DELAY: Duration = Duration::from_millis(500);
+
+    fn delay(&self, attempt: u32) -> Duration {
+        let delay = Self::BASE_DELAY
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        delay / 2 + delay.mul_f64(rand::random::<f64>() / 2.0)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Set the retry policy for this run from the `--retries` and `--retry-max-delay` flags.
+pub fn init_retries(retries: Option<u32>, max_delay: Option<u64>) {
+    let default = RetryPolicy::default();
+    let _ = RETRY.set(RetryPolicy {
+        retries: retries.unwrap_or(default.retries),
+        max_delay: max_delay.map_or(default.max_delay, Duration::from_secs),
+    });
+}
+
+/// Cap the requests in flight at once for this run from the `--concurrency` flag.
+pub fn init_concurrency(limit: Option<usize>) {
+    let limit = limit.unwrap_or(DEFAULT_CONCURRENCY).max(1);
+    let _ = CONCURRENCY.set(Semaphore::new(limit));
+}
+
+fn concurrency() -> &'static Semaphore {
+    CONCURRENCY.get_or_init(|| Semaphore::new(DEFAULT_CONCURRENCY))
+}
+
+/// A GraphQL request on its way to a [`SlotApi`].
+#[derive(Debug, Clone)]
+pub struct ApiRequest {
+    pub operation_name: String,
+    /// The JSON body: `query`, `variables` and `operationName`.
+    pub body: Value,
+    /// Set for mutations, and kept across retries, so the API can drop duplicates.
+    pub idempotency_key: Option<String>,
 }
 
+/// Where [`ApiClient`] sends its requests: the Cartridge API over HTTP, or a [`MockApi`] with
+/// canned responses, so code built on the client can be tested without the network.
+#[async_trait]
+pub trait SlotApi: Send + Sync {
+    /// Send `request` and return the GraphQL response body, `data` and `errors` included.
+    async fn execute(&self, request: ApiRequest) -> Result<Value, ApiError>;
+}
+
+#[derive(Clone)]
 pub struct ApiClient {
-    base_url: String,
-    client: reqwest::Client,
+    api: Arc<dyn SlotApi>,
 }
 
 impl ApiClient {
     pub fn new() -> Self {
-        Self {
-            base_url: "https://api.cartridge.gg/query".to_string(),
-            client: reqwest::Client::new(),
+        Self::with_api(HttpApi::new())
+    }
+
+    /// A client backed by `api` instead of the Cartridge API, e.g. a [`MockApi`].
+    pub fn with_api(api: impl SlotApi + 'static) -> Self {
+        Self { api: Arc::new(api) }
+    }
+
+    /// Run a query or mutation generated from a `.graphql` file, failing with
+    /// [`ApiError::Graphql`] if the API reports any error.
+    pub async fn query<Q: GraphQLQuery>(
+        &self,
+        variables: Q::Variables,
+    ) -> Result<Q::ResponseData, ApiError> {
+        let body = Q::build_query(variables);
+
+        // Mutations carry one idempotency key across attempts, so a retried create can't
+        // produce a second deployment.
+        let idempotency_key = body
+            .query
+            .contains(&format!("mutation {}", body.operation_name))
+            .then(|| format!("{:032x}", rand::random::<u128>()));
+
+        tracing::debug!(operation = body.operation_name, "Sending API request");
+        let request = ApiRequest {
+            operation_name: body.operation_name.to_string(),
+            body: serde_json::to_value(&body)?,
+            idempotency_key,
+        };
+        let res: Response<Q::ResponseData> =
+            serde_json::from_value(self.api.execute(request).await?)?;
+
+        if let Some(errors) = res.errors.filter(|errors| !errors.is_empty()) {
+            return Err(ApiError::Graphql(
+                errors.into_iter().map(GraphqlError::from).collect(),
+            ));
         }
+
+        res.data.ok_or(ApiError::EmptyResponse)
     }
 
+    /// Send a raw GraphQL request. It's retried like a query, so don't use it for mutations.
     pub async fn post<R: DeserializeOwned, T: Serialize + ?Sized>(
         &self,
         body: &T,
     ) -> Result<Response<R>, ApiError> {
-        let credentials = Credentials::load()
-            .map_err(|_| {
-                anyhow::anyhow!("Failed to load credentials. Login with `slot auth login`.")
-            })
-            .map_err(ApiError::CredentialsError)?;
+        let body = serde_json::to_value(body)?;
+        let request = ApiRequest {
+            operation_name: body["operationName"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            body,
+            idempotency_key: None,
+        };
+        Ok(serde_json::from_value(self.api.execute(request).await?)?)
+    }
+}
 
-        let res = self
+/// The Cartridge API, with retries, the `--concurrency` limit and token refresh.
+struct HttpApi {
+    base_url: String,
+    client: reqwest::Client,
+    retry: RetryPolicy,
+}
+
+#[async_trait]
+impl SlotApi for HttpApi {
+    async fn execute(&self, request: ApiRequest) -> Result<Value, ApiError> {
+        self.send_with_retries(&request.body, request.idempotency_key.as_deref())
+            .await
+    }
+}
+
+impl HttpApi {
+    fn new() -> Self {
+        Self {
+            base_url: format!("{}/query", constant::api_url()),
+            client: reqwest::Client::new(),
+            retry: RETRY.get().copied().unwrap_or_default(),
+        }
+    }
+
+    async fn send_with_retries(
+        &self,
+        body: &Value,
+        idempotency_key: Option<&str>,
+    ) -> Result<Value, ApiError> {
+        let mut credentials = Credentials::load()?;
+        let mut refreshed = false;
+        let mut attempt = 0;
+
+        loop {
+            let res = self.send(&credentials, body, idempotency_key).await;
+
+            // Mutations are only replayed when they never reached the API: the server may not
+            // honor the idempotency key, and a timed out create may still go through.
+            // Rate limited requests were turned away unprocessed, so those are always safe.
+            let transient = match &res {
+                Ok(res) if res.status() == StatusCode::TOO_MANY_REQUESTS => true,
+                Ok(res) => idempotency_key.is_none() && res.status().is_server_error(),
+                Err(e) => e.is_connect() || (idempotency_key.is_none() && e.is_timeout()),
+            };
+            if transient && attempt < self.retry.retries {
+                // A server asking for longer than `--retry-max-delay` still waits no longer.
+                let delay = res
+                    .as_ref()
+                    .ok()
+                    .and_then(retry_after)
+                    .map(|delay| delay.min(self.retry.max_delay))
+                    .unwrap_or_else(|| self.retry.delay(attempt));
+                attempt += 1;
+                tracing::debug!(
+                    "Retrying API request ({attempt}/{}) in {delay:?}",
+                    self.retry.retries
+                );
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+
+            let res = res.map_err(ApiError::ReqwestError)?;
+
+            // The access token expired, refresh it once and replay the request. Concurrent
+            // requests share credentials, so another one may have refreshed them already.
+            if res.status() == StatusCode::UNAUTHORIZED && !refreshed {
+                let latest = Credentials::load()?;
+                credentials = if latest.access_token != credentials.access_token {
+                    latest
+                } else {
+                    self.refresh(credentials).await?
+                };
+                refreshed = true;
+                continue;
+            }
+
+            if res.status() == StatusCode::UNAUTHORIZED || res.status() == StatusCode::FORBIDDEN {
+                return Err(ApiError::CredentialsError(Error::CredentialsExpired));
+            }
+
+            if res.status().is_server_error() || res.status() == StatusCode::TOO_MANY_REQUESTS {
+                return Err(ApiError::ReqwestError(res.error_for_status().unwrap_err()));
+            }
+
+            return res.json().await.map_err(ApiError::ReqwestError);
+        }
+    }
+
+    async fn send(
+        &self,
+        credentials: &Credentials,
+        body: &Value,
+        idempotency_key: Option<&str>,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self
             .client
             .post(&self.base_url)
             .header(
                 "Authorization",
                 format!("Bearer {}", credentials.access_token),
             )
-            .json(body)
+            .json(body);
+        if let Some(key) = idempotency_key {
+            request = request.header("Idempotency-Key", key);
+        }
+
+        // The semaphore is never closed, so acquiring can't fail.
+        let _permit = concurrency().acquire().await.unwrap();
+        request.send().await
+    }
+
+    /// Trade the refresh token for a new access token and persist it.
+    async fn refresh(&self, credentials: Credentials) -> Result<Credentials, ApiError> {
+        let Some(refresh_token) = credentials.refresh_token else {
+            return Err(ApiError::CredentialsError(Error::CredentialsExpired));
+        };
+
+        let res = self
+            .client
+            .post(format!("{}/oauth2/token", constant::api_url()))
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", &refresh_token),
+            ])
             .send()
             .await
             .map_err(ApiError::ReqwestError)?;
 
-        if res.status() == 403 {
-            return Err(ApiError::CredentialsError(anyhow::anyhow!(
-                "Invalid token, authenticate with `slot auth login`"
-            )));
+        if !res.status().is_success() {
+            return Err(ApiError::CredentialsError(Error::CredentialsExpired));
         }
 
-        let res: Response<R> = res.json().await.map_err(ApiError::ReqwestError)?;
+        let mut refreshed: Credentials = res.json().await.map_err(ApiError::ReqwestError)?;
+        // Servers may omit the refresh token when it is not rotated.
+        refreshed.refresh_token.get_or_insert(refresh_token);
+        refreshed.write()?;
+
+        Ok(refreshed)
+    }
+}
+
+/// The delay a `Retry-After` header asks for. Only the delay-seconds form is supported.
+fn retry_after(res: &reqwest::Response) -> Option<Duration> {
+    let seconds = res.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    seconds.trim().parse().ok().map(Duration::from_secs)
+}
 
-        Ok(res)
+impl Default for ApiClient {
+    fn default() -> Self {
+        Self::new()
     }
 }
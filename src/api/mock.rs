@@ -0,0 +1,84 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::{ApiError, ApiRequest, GraphqlError, SlotApi};
+
+/// Canned responses keyed by operation name, for testing code built on
+/// [`ApiClient`](super::ApiClient) without the network. Clones share their responses and
+/// recorded requests, so keep one to inspect what the client sent.
+///
+/// ```
+/// # async fn example() -> anyhow::Result<()> {
+/// use serde_json::json;
+/// use slot::{api::{ApiClient, MockApi}, deployments::{Deployments, Service}};
+///
+/// let api = MockApi::new();
+/// api.respond("DeleteDeployment", json!({ "deleteDeployment": true }));
+///
+/// let deployments = Deployments::with_client(ApiClient::with_api(api.clone()));
+/// deployments.delete("my-project", &Service::Torii).await?;
+///
+/// assert_eq!(api.requests()[0].body["variables"]["project"], "my-project");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct MockApi {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    responses: HashMap<String, Value>,
+    requests: Vec<ApiRequest>,
+}
+
+impl MockApi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer every `operation` request with `data`, replacing any earlier response.
+    pub fn respond(&self, operation: &str, data: Value) -> &Self {
+        self.insert(operation, json!({ "data": data }))
+    }
+
+    /// Answer every `operation` request with a GraphQL error, e.g. code `NOT_FOUND`.
+    pub fn respond_error(&self, operation: &str, message: &str, code: Option<&str>) -> &Self {
+        let error = json!({ "message": message, "extensions": { "code": code } });
+        self.insert(operation, json!({ "data": null, "errors": [error] }))
+    }
+
+    /// Every request sent so far, oldest first.
+    pub fn requests(&self) -> Vec<ApiRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    fn insert(&self, operation: &str, response: Value) -> &Self {
+        let mut state = self.state.lock().unwrap();
+        state.responses.insert(operation.to_string(), response);
+        self
+    }
+}
+
+#[async_trait]
+impl SlotApi for MockApi {
+    async fn execute(&self, request: ApiRequest) -> Result<Value, ApiError> {
+        let mut state = self.state.lock().unwrap();
+        let response = state.responses.get(&request.operation_name).cloned();
+        let operation = request.operation_name.clone();
+        state.requests.push(request);
+
+        response.ok_or_else(|| {
+            ApiError::Graphql(vec![GraphqlError {
+                message: format!("MockApi has no response for {operation}."),
+                code: None,
+            }])
+        })
+    }
+}
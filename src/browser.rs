@@ -1,18 +1,147 @@
-use anyhow::Result;
-use std::net::SocketAddr;
+use anyhow::{anyhow, Result};
+use qrcode::{render::unicode, QrCode};
+use std::{env, fs, net::SocketAddr, path::Path, process::Command};
 use urlencoding::encode;
 
+use crate::constant;
+
 pub struct Browser;
 
+/// Where the CLI runs, as far as opening a browser goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Environment {
+    Desktop,
+    /// Windows Subsystem for Linux, where the browser is a Windows program.
+    Wsl,
+    /// Logged in over SSH: a browser would open on the remote machine, if at all.
+    Ssh,
+    /// Linux without a display server.
+    Headless,
+}
+
+impl Environment {
+    pub fn detect() -> Self {
+        if ["SSH_CONNECTION", "SSH_CLIENT", "SSH_TTY"]
+            .iter()
+            .any(|var| env::var_os(var).is_some())
+        {
+            return Environment::Ssh;
+        }
+
+        if cfg!(target_os = "linux") {
+            let wsl = env::var_os("WSL_DISTRO_NAME").is_some()
+                || fs::read_to_string("/proc/version")
+                    .is_ok_and(|version| version.to_lowercase().contains("microsoft"));
+            if wsl {
+                return Environment::Wsl;
+            }
+        }
+
+        let headless = cfg!(all(unix, not(target_os = "macos")))
+            && env::var_os("DISPLAY").is_none()
+            && env::var_os("WAYLAND_DISPLAY").is_none();
+        if headless {
+            Environment::Headless
+        } else {
+            Environment::Desktop
+        }
+    }
+}
+
 impl Browser {
-    pub async fn open(local_addr: &SocketAddr) -> Result<()> {
-        let callback_uri = format!("http://{local_addr}/callback").replace("[::1]", "localhost");
+    /// Open the login page with `command`, falling back to `$BROWSER` and then the system
+    /// browser. If nothing can be launched the URL is printed along with a QR code.
+    pub async fn open(local_addr: &SocketAddr, state: &str, command: Option<&str>) -> Result<()> {
+        // The browser reaches us through localhost, even when bound to all interfaces.
+        let callback_uri = if local_addr.ip().is_loopback() || local_addr.ip().is_unspecified() {
+            format!("http://localhost:{}/callback", local_addr.port())
+        } else {
+            format!("http://{local_addr}/callback")
+        };
         let encoded_callback_uri = encode(&callback_uri);
-        let url = format!("https://x.cartridge.gg/slot/auth?callback_uri={encoded_callback_uri}");
+        let url = format!(
+            "{}/slot/auth?callback_uri={encoded_callback_uri}&state={}",
+            constant::keychain_url(),
+            encode(state)
+        );
 
-        println!("Your browser has been opened to visit: \n\n    {url}\n");
-        webbrowser::open(&url)?;
+        match Self::launch(&url, command) {
+            Ok(()) => println!("Your browser has been opened to visit: \n\n    {url}\n"),
+            Err(e) => {
+                println!(
+                    "Unable to open a browser ({e}). Visit this URL to continue: \n\n    {url}\n"
+                );
+                if let Ok(code) = QrCode::new(&url) {
+                    println!(
+                        "{}",
+                        code.render::<unicode::Dense1x2>().quiet_zone(true).build()
+                    );
+                }
+            }
+        }
 
         Ok(())
     }
+
+    fn launch(url: &str, command: Option<&str>) -> Result<()> {
+        let command = command
+            .map(String::from)
+            .or_else(|| env::var("BROWSER").ok().filter(|b| !b.is_empty()));
+
+        let Some(command) = command else {
+            return Self::launch_default(url);
+        };
+
+        // `$BROWSER` may list several launchers separated by `:`; use the first that starts.
+        for launcher in command.split(':').filter(|l| !l.is_empty()) {
+            let mut parts = launcher.split_whitespace();
+            let Some(program) = parts.next() else {
+                continue;
+            };
+
+            let mut args: Vec<String> = parts.map(String::from).collect();
+            if args.iter().any(|arg| arg.contains("%s")) {
+                args.iter_mut()
+                    .for_each(|arg| *arg = arg.replace("%s", url));
+            } else {
+                args.push(url.to_string());
+            }
+
+            if Command::new(program).args(&args).spawn().is_ok() {
+                return Ok(());
+            }
+        }
+
+        Err(anyhow!("failed to run `{command}`"))
+    }
+
+    /// The system browser, unless there's clearly none to open.
+    fn launch_default(url: &str) -> Result<()> {
+        match Environment::detect() {
+            Environment::Ssh => Err(anyhow!("running over SSH")),
+            Environment::Headless => Err(anyhow!("no display found")),
+            // `wslview` from wslu hands the URL to the Windows browser.
+            Environment::Wsl if on_path("wslview") => {
+                Command::new("wslview").arg(url).spawn()?;
+                Ok(())
+            }
+            Environment::Wsl => Err(anyhow!("running in WSL without `wslview`; install wslu")),
+            Environment::Desktop => Ok(webbrowser::open(url)?),
+        }
+    }
+}
+
+pub fn on_path(program: &str) -> bool {
+    env::var_os("PATH")
+        .is_some_and(|path| env::split_paths(&path).any(|dir| is_executable(&dir.join(program))))
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    path.is_file()
 }
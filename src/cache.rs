@@ -0,0 +1,227 @@
+//! On-disk copies of read-only API responses, so listing and describing deployments keeps
+//! working, with a warning, while the API is unreachable.
+
+use std::{
+    env, fs,
+    future::Future,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{api::ApiError, credential};
+
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    /// Seconds since the Unix epoch.
+    fetched_at: u64,
+    value: T,
+}
+
+/// Run `fetch` and cache its result under `key`. A cached result younger than `max_age` is
+/// returned without fetching, and any cached result is returned if the API can't be reached.
+pub(crate) async fn fetch_or_cached<T, F>(key: &str, max_age: Duration, fetch: F) -> Result<T>
+where
+    T: Serialize + DeserializeOwned,
+    F: Future<Output = Result<T>>,
+{
+    cached_at(path(key), max_age, fetch).await
+}
+
+/// [`fetch_or_cached`] with the cache file at `path`, or no cache when there's nowhere to keep it.
+async fn cached_at<T, F>(path: Option<PathBuf>, max_age: Duration, fetch: F) -> Result<T>
+where
+    T: Serialize + DeserializeOwned,
+    F: Future<Output = Result<T>>,
+{
+    let cached = match path.as_deref().and_then(read::<T>) {
+        Some(entry) if age(&entry) < max_age => return Ok(entry.value),
+        cached => cached,
+    };
+
+    match fetch.await {
+        Ok(value) => {
+            if let Some(path) = &path {
+                write(path, &value);
+            }
+            Ok(value)
+        }
+        Err(e) if is_unreachable(&e) => match cached {
+            Some(entry) => {
+                eprintln!(
+                    "Warning: the API is unreachable ({e}), showing results from {}s ago.",
+                    age(&entry).as_secs()
+                );
+                Ok(entry.value)
+            }
+            None => Err(e),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+fn age<T>(entry: &Entry<T>) -> Duration {
+    now().saturating_sub(Duration::from_secs(entry.fetched_at))
+}
+
+fn now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+fn is_unreachable(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<ApiError>(),
+        Some(ApiError::ReqwestError(e))
+            if e.is_connect() || e.is_timeout() || e.status().is_some_and(|s| s.is_server_error())
+    )
+}
+
+fn read<T: DeserializeOwned>(path: &Path) -> Option<Entry<T>> {
+    let contents = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Caching is best effort: a read-only command shouldn't fail because the cache can't be written.
+fn write<T: Serialize>(path: &Path, value: &T) {
+    let entry = Entry {
+        fetched_at: now().as_secs(),
+        value,
+    };
+    if let Ok(contents) = serde_json::to_string(&entry) {
+        let _ = fs::create_dir_all(path.parent().unwrap());
+        let _ = fs::write(path, contents);
+    }
+}
+
+/// Responses are kept per account, since each one sees different deployments.
+fn path(key: &str) -> Option<PathBuf> {
+    let token = env::var("SLOT_AUTH_TOKEN")
+        .ok()
+        .filter(|token| !token.is_empty());
+    let account = match token {
+        Some(token) => token_dir(&token),
+        None => credential::active_profile().ok()?,
+    };
+    let file: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    let mut path = dirs::cache_dir()?;
+    path.push("slot");
+    path.push(account);
+    path.push(format!("{file}.json"));
+    Some(path)
+}
+
+/// `SLOT_AUTH_TOKEN` picks the account regardless of the profile, so its responses go under
+/// the token instead. Only a digest of it, since the cache isn't private.
+fn token_dir(token: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(token.as_bytes()));
+    format!("token-{}", &digest[..16])
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+    use crate::storage::TempFile;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    /// A cache file holding `value`, fetched `age` ago.
+    fn cached(value: &str, age: Duration) -> TempFile {
+        let entry = Entry {
+            fetched_at: (now() - age).as_secs(),
+            value,
+        };
+        TempFile::new(".json", serde_json::to_string(&entry).unwrap().as_bytes()).unwrap()
+    }
+
+    async fn unreachable() -> anyhow::Error {
+        // Nothing listens on the discard port, so the connection is refused.
+        let e = reqwest::get("http://127.0.0.1:9").await.unwrap_err();
+        ApiError::ReqwestError(e).into()
+    }
+
+    #[tokio::test]
+    async fn fresh_entries_are_returned_without_fetching() {
+        let file = cached("cached", Duration::ZERO);
+
+        let fetch = async { panic!("a fresh entry shouldn't be fetched") };
+        let value: String = cached_at(Some(file.path().into()), HOUR, fetch)
+            .await
+            .unwrap();
+
+        assert_eq!(value, "cached");
+    }
+
+    #[tokio::test]
+    async fn stale_entries_are_fetched_and_replaced() {
+        let file = cached("cached", 2 * HOUR);
+
+        let fetch = async { Ok("fetched".to_string()) };
+        let value = cached_at(Some(file.path().into()), HOUR, fetch)
+            .await
+            .unwrap();
+
+        assert_eq!(value, "fetched");
+        let entry = read::<String>(file.path()).unwrap();
+        assert_eq!(entry.value, "fetched");
+        assert!(age(&entry) < HOUR);
+    }
+
+    #[tokio::test]
+    async fn stale_entries_are_used_while_the_api_is_unreachable() {
+        let file = cached("cached", 2 * HOUR);
+
+        let error = unreachable().await;
+        let value: String = cached_at(Some(file.path().into()), HOUR, async { Err(error) })
+            .await
+            .unwrap();
+
+        assert_eq!(value, "cached");
+    }
+
+    #[tokio::test]
+    async fn other_errors_are_not_hidden_by_the_cache() {
+        let file = cached("cached", 2 * HOUR);
+
+        let fetch = async { Err::<String, _>(anyhow!("Deployment my-project not found.")) };
+        let err = cached_at(Some(file.path().into()), HOUR, fetch)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "Deployment my-project not found.");
+    }
+
+    #[tokio::test]
+    async fn without_a_cache_the_error_comes_through() {
+        let error = unreachable().await;
+        let result = cached_at::<String, _>(None, HOUR, async { Err(error) }).await;
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn token_caches_are_named_by_digest() {
+        let dir = token_dir("secret-token");
+
+        assert!(dir.starts_with("token-"));
+        assert!(!dir.contains("secret"));
+        assert_eq!(dir, token_dir("secret-token"));
+        assert_ne!(dir, token_dir("other-token"));
+    }
+}
@@ -1,10 +1,44 @@
-use crate::command::Command;
-use clap::Parser;
+use std::path::PathBuf;
+
+use clap::{ArgAction, Parser};
+
+use crate::{command::Command, logging::LogFormat, output::Output};
 
 /// Slot CLI for Cartridge
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
+    #[arg(long, global = true, value_name = "format")]
+    #[arg(help = "Output format. Defaults to the `output` setting, then text.")]
+    pub output: Option<Output>,
+
+    #[arg(long, global = true, value_name = "count")]
+    #[arg(help = "Times to retry API requests that fail transiently. Defaults to 3.")]
+    pub retries: Option<u32>,
+
+    #[arg(long, global = true, value_name = "seconds")]
+    #[arg(help = "Longest delay between retries of API requests. Defaults to 10.")]
+    pub retry_max_delay: Option<u64>,
+
+    #[arg(long, global = true, value_name = "count")]
+    #[arg(
+        help = "Most API requests to run at once, e.g. when destroying a manifest. Defaults to 4."
+    )]
+    pub concurrency: Option<usize>,
+
+    // Long only: `-v` is already `--version` on the service subcommands.
+    #[arg(long, global = true, action = ArgAction::Count)]
+    #[arg(help = "Log more details to stderr. Repeat for debug and trace logs.")]
+    pub verbose: u8,
+
+    #[arg(long, global = true, value_name = "format", default_value = "text")]
+    #[arg(help = "Format of log lines.")]
+    pub log_format: LogFormat,
+
+    #[arg(long, global = true, value_name = "path")]
+    #[arg(help = "Also append debug logs to this file.")]
+    pub log_file: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: Command,
 }
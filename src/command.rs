@@ -1,11 +1,29 @@
+pub mod account;
 pub mod auth;
+pub mod ci;
+pub mod completions;
+pub mod config;
+pub mod dashboard;
 pub mod deployments;
+pub mod doctor;
+pub mod teams;
+pub mod update;
+pub mod version;
 
 use anyhow::Result;
 use clap::Subcommand;
 
+use account::Account;
 use auth::Auth;
+use ci::Ci;
+use completions::CompletionsArgs;
+use config::Config;
+use dashboard::DashboardArgs;
 use deployments::Deployments;
+use doctor::DoctorArgs;
+use teams::Teams;
+use update::UpdateArgs;
+use version::VersionArgs;
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Subcommand, Debug)]
@@ -14,15 +32,46 @@ pub enum Command {
     #[command(about = "Manage auth credentials for the Slot CLI.", aliases = ["a"])]
     Auth(Auth),
     #[command(subcommand)]
+    #[command(about = "Look up and deploy Cartridge controller accounts.")]
+    Account(Account),
+    #[command(subcommand)]
     #[command(about = "Manage Slot deployments.", aliases = ["d"])]
     Deployments(Deployments),
+    #[command(about = "Monitor deployments and their logs in an interactive dashboard.")]
+    Dashboard(DashboardArgs),
+    #[command(subcommand)]
+    #[command(about = "View Slot teams and their members.", aliases = ["t"])]
+    Teams(Teams),
+    #[command(subcommand)]
+    #[command(about = "Manage default settings for the Slot CLI.")]
+    Config(Config),
+    #[command(subcommand)]
+    #[command(about = "Integrate Slot with CI providers.")]
+    Ci(Ci),
+    #[command(about = "Check the local setup and print fixes for what's wrong.")]
+    Doctor(DoctorArgs),
+    #[command(about = "Print a shell completion script, e.g. `slot completions zsh`.")]
+    Completions(CompletionsArgs),
+    #[command(about = "Update the Slot CLI to the latest release.")]
+    Update(UpdateArgs),
+    #[command(about = "Print the CLI version, optionally checking for a newer release.")]
+    Version(VersionArgs),
 }
 
 impl Command {
     pub async fn run(&self) -> Result<()> {
         match &self {
             Command::Auth(cmd) => cmd.run().await,
+            Command::Account(cmd) => cmd.run().await,
             Command::Deployments(cmd) => cmd.run().await,
+            Command::Dashboard(args) => args.run().await,
+            Command::Teams(cmd) => cmd.run().await,
+            Command::Config(cmd) => cmd.run(),
+            Command::Ci(cmd) => cmd.run().await,
+            Command::Doctor(args) => args.run().await,
+            Command::Completions(args) => args.run(),
+            Command::Update(args) => args.run().await,
+            Command::Version(args) => args.run().await,
         }
     }
 }
@@ -0,0 +1,87 @@
+use anyhow::{anyhow, Result};
+use clap::Args;
+use graphql_client::GraphQLQuery;
+use serde_json::json;
+use starknet::{
+    core::utils::parse_cairo_short_string,
+    providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider},
+};
+
+use crate::{api::ApiClient, output};
+
+use super::ChainArgs;
+
+use self::deploy_account::Variables;
+
+type ChainID = String;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.json",
+    query_path = "src/command/account/deploy.graphql",
+    response_derives = "Debug"
+)]
+pub struct DeployAccount;
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Deploy options")]
+pub struct DeployArgs {
+    #[arg(help = "The username of the controller account.")]
+    pub username: String,
+
+    #[arg(long, value_name = "chain_id")]
+    #[arg(help = "Chain to deploy on. Read from the RPC when omitted.")]
+    pub chain_id: Option<String>,
+
+    #[command(flatten)]
+    pub chain: ChainArgs,
+}
+
+impl DeployArgs {
+    pub async fn run(&self) -> Result<()> {
+        let chain_id = match (&self.chain_id, self.chain.rpc_url()?) {
+            (Some(chain_id), _) => chain_id.clone(),
+            (None, Some(url)) => {
+                let chain_id = JsonRpcClient::new(HttpTransport::new(url))
+                    .chain_id()
+                    .await?;
+                // Katana and Starknet chain ids are short strings such as `KATANA`.
+                parse_cairo_short_string(&chain_id).unwrap_or_else(|_| format!("{chain_id:#x}"))
+            }
+            (None, None) => {
+                return Err(anyhow!(
+                    "Pass `--chain-id`, or `--rpc-url` or `--project` to read it from the chain."
+                ))
+            }
+        };
+
+        let client = ApiClient::new();
+        let contract = client
+            .query::<DeployAccount>(Variables {
+                username: self.username.clone(),
+                chain_id: chain_id.clone(),
+            })
+            .await?
+            .deploy_account;
+
+        if output::is_json() {
+            output::print_json(&json!({
+                "username": self.username,
+                "chain_id": chain_id,
+                "address": contract.id,
+                "transaction_hash": contract
+                    .deploy_transaction
+                    .map(|transaction| transaction.transaction_hash),
+            }))?;
+            return Ok(());
+        }
+
+        println!("Deployed {} on {chain_id} 🚀", self.username);
+        println!("\nAddress: {}", contract.id);
+        if let Some(transaction) = contract.deploy_transaction {
+            println!("Transaction: {}", transaction.transaction_hash);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use anyhow::{anyhow, Result};
+use clap::Args;
+use graphql_client::GraphQLQuery;
+use serde_json::json;
+use starknet::{
+    core::types::{BlockId, BlockTag, FieldElement, StarknetError},
+    providers::{
+        jsonrpc::HttpTransport, JsonRpcClient, MaybeUnknownErrorCode, Provider, ProviderError,
+        StarknetErrorWithMessage,
+    },
+};
+
+use crate::{api::ApiClient, output};
+
+use super::ChainArgs;
+
+use self::describe_account::Variables;
+
+type Time = String;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.json",
+    query_path = "src/command/account/describe.graphql",
+    response_derives = "Debug"
+)]
+pub struct DescribeAccount;
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Describe options")]
+pub struct DescribeArgs {
+    #[arg(help = "The username of the controller account.")]
+    pub username: String,
+
+    #[command(flatten)]
+    pub chain: ChainArgs,
+}
+
+impl DescribeArgs {
+    pub async fn run(&self) -> Result<()> {
+        let client = ApiClient::new();
+        let account = client
+            .query::<DescribeAccount>(Variables {
+                username: self.username.clone(),
+            })
+            .await?
+            .account
+            .ok_or_else(|| anyhow!("No account named {}.", self.username))?;
+
+        // Only look at the chain when one was given and the account has an address on it.
+        let class_hash = match (self.chain.rpc_url()?, &account.contract_address) {
+            (Some(url), Some(address)) => {
+                let provider = JsonRpcClient::new(HttpTransport::new(url));
+                let address = FieldElement::from_hex_be(address)?;
+                match provider
+                    .get_class_hash_at(BlockId::Tag(BlockTag::Latest), address)
+                    .await
+                {
+                    Ok(class_hash) => Some(Some(format!("{class_hash:#x}"))),
+                    Err(ProviderError::StarknetError(StarknetErrorWithMessage {
+                        code: MaybeUnknownErrorCode::Known(StarknetError::ContractNotFound),
+                        ..
+                    })) => Some(None),
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            _ => None,
+        };
+
+        if output::is_json() {
+            return output::print_json(&json!({
+                "username": account.id,
+                "name": account.name,
+                "address": account.contract_address,
+                "type": format!("{:?}", account.type_),
+                "created_at": account.created_at,
+                "deployed": class_hash.as_ref().map(Option::is_some),
+                "class_hash": class_hash.flatten(),
+            }));
+        }
+
+        println!("Username: {}", account.id);
+        if let Some(name) = &account.name {
+            println!("Name: {name}");
+        }
+        println!(
+            "Address: {}",
+            account.contract_address.as_deref().unwrap_or("None")
+        );
+        println!("Type: {:?}", account.type_);
+        println!("Created At: {}", account.created_at);
+        match class_hash {
+            Some(Some(class_hash)) => {
+                println!("Deployed: yes");
+                println!("Class Hash: {class_hash}");
+            }
+            Some(None) => println!("Deployed: no"),
+            None => {}
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use url::Url;
+
+use crate::constant;
+
+use self::{deploy::DeployArgs, describe::DescribeArgs};
+
+mod deploy;
+mod describe;
+
+#[derive(Subcommand, Debug)]
+pub enum Account {
+    #[command(about = "Look up a controller account and whether it is deployed on a chain.")]
+    Describe(DescribeArgs),
+    #[command(about = "Deploy a controller account on a chain, e.g. a katana deployment.")]
+    Deploy(DeployArgs),
+}
+
+impl Account {
+    pub async fn run(&self) -> Result<()> {
+        match &self {
+            Account::Describe(args) => args.run().await,
+            Account::Deploy(args) => args.run().await,
+        }
+    }
+}
+
+/// The chain to check an account against: any RPC URL, or a katana deployment by project.
+#[derive(Debug, Args)]
+#[group(multiple = false)]
+pub struct ChainArgs {
+    #[arg(long, value_name = "url")]
+    #[arg(help = "JSON-RPC endpoint of the chain.")]
+    pub rpc_url: Option<Url>,
+
+    #[arg(long, value_name = "project")]
+    #[arg(help = "Use the RPC of this project's katana deployment.")]
+    pub project: Option<String>,
+}
+
+impl ChainArgs {
+    pub fn rpc_url(&self) -> Result<Option<Url>> {
+        match (&self.rpc_url, &self.project) {
+            (Some(url), _) => Ok(Some(url.clone())),
+            (None, Some(project)) => Ok(Some(Url::parse(&format!(
+                "{}/x/{project}/katana",
+                constant::api_url()
+            ))?)),
+            (None, None) => Ok(None),
+        }
+    }
+}
@@ -1,16 +1,16 @@
 use anyhow::Result;
 use clap::Args;
-use graphql_client::{GraphQLQuery, Response};
+use graphql_client::GraphQLQuery;
 
-use crate::api::ApiClient;
+use crate::{api::ApiClient, output};
 
-use self::me::{ResponseData, Variables};
+use self::me::Variables;
 
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "schema.json",
     query_path = "src/command/auth/info.graphql",
-    response_derives = "Debug"
+    response_derives = "Debug, Serialize"
 )]
 pub struct Me;
 
@@ -19,18 +19,14 @@ pub struct InfoArgs {}
 
 impl InfoArgs {
     pub async fn run(&self) -> Result<()> {
-        let request_body = Me::build_query(Variables {});
-
         let client = ApiClient::new();
-        let res: Response<ResponseData> = client.post(&request_body).await?;
-        if let Some(errors) = res.errors {
-            for err in errors {
-                println!("Error: {}", err.message);
-            }
-            return Ok(());
+        let data = client.query::<Me>(Variables {}).await?;
+
+        if output::is_json() {
+            return output::print_json(&data);
         }
 
-        print!("{:?}", res.data.unwrap());
+        print!("{:?}", data);
 
         Ok(())
     }
@@ -1,36 +1,184 @@
-use crate::{browser::Browser, server::LocalServer};
-use anyhow::Result;
+use crate::{
+    browser::{Browser, Environment},
+    credential, output,
+    server::{self, LocalServer},
+};
+use anyhow::{anyhow, Result};
 use clap::Args;
+use std::{env, io, net::TcpListener, time::Duration};
 use tokio::runtime::Runtime;
+use url::Url;
 
 #[derive(Debug, Args)]
-pub struct LoginArgs {}
+pub struct LoginArgs {
+    #[arg(long, default_value = "300")]
+    #[arg(value_name = "seconds")]
+    #[arg(help = "How long to wait for the browser callback before giving up.")]
+    pub timeout: u64,
+
+    #[arg(long, env = "SLOT_CALLBACK_PORT", default_value = "0")]
+    #[arg(value_name = "port")]
+    #[arg(help = "Port for the local callback server. Picks a free port by default.")]
+    pub callback_port: u16,
+
+    #[arg(long, env = "SLOT_CALLBACK_HOST", default_value = "localhost")]
+    #[arg(value_name = "host")]
+    #[arg(help = "Address the local callback server binds to, e.g. 0.0.0.0 in containers.")]
+    pub callback_host: String,
+
+    #[arg(long)]
+    #[arg(
+        help = "Paste the redirect URL from the browser instead of waiting for the callback. \
+                  Use this when the browser runs on another machine."
+    )]
+    pub manual: bool,
+
+    #[arg(long, value_name = "command")]
+    #[arg(
+        help = "Command used to open the login page. Defaults to $BROWSER, then the system browser."
+    )]
+    pub browser: Option<String>,
+
+    #[arg(long, value_name = "profile")]
+    #[arg(help = "Log in under this profile and make it the active one.")]
+    pub profile: Option<String>,
+}
 
 impl LoginArgs {
     pub fn run(&self) -> Result<()> {
+        output::text_only("slot auth login")?;
+        let profile = match &self.profile {
+            Some(profile) => {
+                credential::validate_profile(profile)?;
+                profile.clone()
+            }
+            None => credential::active_profile()?,
+        };
+
         let rt = Runtime::new()?;
+        let timeout = Duration::from_secs(self.timeout);
+        let host = self.callback_host.clone();
+        let port = self.callback_port;
+        let browser = self.browser.clone();
 
-        let handler = std::thread::spawn(move || {
-            let server = LocalServer::new().expect("Failed to start a server");
-            let addr = server.local_addr().unwrap();
-
-            let res = rt.block_on(async { tokio::join!(server.start(), Browser::open(&addr)) });
-
-            match res {
-                (Err(e), _) => {
-                    eprintln!("Server error: {e}");
-                }
-                (_, Err(e)) => {
-                    eprintln!("Browser error: {e}");
-                }
-                _ => {
-                    // println!("Success");
-                }
-            }
-        });
+        let handler = if self.manual {
+            std::thread::spawn(move || {
+                rt.block_on(manual_login(&host, port, browser.as_deref(), &profile))
+            })
+        } else {
+            std::thread::spawn(move || server_login(rt, &host, port, browser, profile, timeout))
+        };
+        handler.join().unwrap()?;
 
-        handler.join().unwrap();
+        // Only now, so a failed login leaves the previous profile active.
+        if let Some(profile) = &self.profile {
+            credential::set_active_profile(profile)?;
+        }
+        println!("You are now logged in!\n");
 
         Ok(())
     }
 }
+
+fn server_login(
+    rt: Runtime,
+    host: &str,
+    port: u16,
+    browser: Option<String>,
+    profile: String,
+    timeout: Duration,
+) -> Result<()> {
+    let server = LocalServer::new(host, port, profile)?;
+    let addr = server.local_addr()?;
+    let state = server.state().to_string();
+
+    // The callback only reaches this machine if the port is forwarded to it.
+    let remote = browser.is_none() && env::var_os("BROWSER").is_none();
+    if remote && Environment::detect() == Environment::Ssh {
+        let port = addr.port();
+        println!(
+            "Running over SSH. To log in with a browser on your machine, forward the \
+             callback port first: `ssh -L {port}:localhost:{port} <host>`. \
+             Or use `slot auth login --manual`.\n"
+        );
+    }
+
+    let (served, opened) = rt.block_on(async {
+        tokio::join!(
+            server.start(timeout),
+            Browser::open(&addr, &state, browser.as_deref())
+        )
+    });
+
+    if let Err(e) = opened {
+        eprintln!("Browser error: {e}");
+    }
+    served?;
+
+    Ok(())
+}
+
+async fn manual_login(host: &str, port: u16, browser: Option<&str>, profile: &str) -> Result<()> {
+    // Nothing listens on the callback address; the redirect only has to carry the code.
+    let addr = TcpListener::bind((host, port))?.local_addr()?;
+    let state = server::random_state();
+
+    Browser::open(&addr, &state, browser).await?;
+
+    println!("After approving, paste the URL your browser was redirected to (or just the code):");
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    let code = parse_code(input.trim(), &state)?;
+    let cred = server::exchange_code(&code).await?;
+    cred.write_profile(profile)?;
+
+    Ok(())
+}
+
+fn parse_code(input: &str, state: &str) -> Result<String> {
+    if input.is_empty() {
+        return Err(anyhow!("No authorization code provided."));
+    }
+
+    // A pasted code is taken as is, but a redirect URL has to prove it's from this attempt.
+    let url = match Url::parse(input) {
+        Ok(url) if url.scheme() == "http" || url.scheme() == "https" => url,
+        _ => return Ok(input.to_string()),
+    };
+
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+
+    if param("state").as_deref() != Some(state) {
+        return Err(anyhow!(
+            "Redirect URL does not belong to this login attempt."
+        ));
+    }
+
+    param("code").ok_or_else(|| anyhow!("Redirect URL has no authorization code. Try again."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redirect_urls_need_the_state_of_this_attempt() {
+        let url = "http://localhost:1234/callback?code=abc";
+
+        assert_eq!(parse_code(&format!("{url}&state=s1"), "s1").unwrap(), "abc");
+        assert!(parse_code(&format!("{url}&state=s2"), "s1").is_err());
+        assert!(parse_code(url, "s1").is_err());
+    }
+
+    #[test]
+    fn bare_codes_are_taken_as_is() {
+        assert_eq!(parse_code("abc", "s1").unwrap(), "abc");
+        assert_eq!(parse_code("abc:def", "s1").unwrap(), "abc:def");
+        assert!(parse_code("", "s1").is_err());
+    }
+}
@@ -0,0 +1,24 @@
+use anyhow::Result;
+use clap::Args;
+use serde_json::json;
+
+use crate::{
+    credential::{self, Credentials},
+    output,
+};
+
+#[derive(Debug, Args)]
+pub struct LogoutArgs {}
+
+impl LogoutArgs {
+    pub fn run(&self) -> Result<()> {
+        Credentials::delete()?;
+        let profile = credential::active_profile()?;
+        if output::is_json() {
+            return output::print_json(&json!({ "profile": profile, "logged_in": false }));
+        }
+        println!("Logged out of profile `{profile}`.");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use anyhow::Result;
+use clap::Args;
+use serde_json::json;
+
+use crate::{
+    credential::{self, Credentials},
+    output,
+};
+
+#[derive(Debug, Args)]
+pub struct MigrateKeyringArgs {}
+
+impl MigrateKeyringArgs {
+    pub fn run(&self) -> Result<()> {
+        Credentials::migrate_to_keyring()?;
+        if output::is_json() {
+            let profile = credential::active_profile()?;
+            return output::print_json(&json!({ "profile": profile, "keychain": true }));
+        }
+        println!("Credentials moved to the OS keychain.");
+
+        Ok(())
+    }
+}
@@ -1,24 +1,47 @@
 use anyhow::Result;
 use clap::Subcommand;
 
-use self::{info::InfoArgs, login::LoginArgs};
+use self::{
+    info::InfoArgs, login::LoginArgs, logout::LogoutArgs, migrate_keyring::MigrateKeyringArgs,
+    status::StatusArgs, switch::SwitchArgs, token::TokenArgs,
+};
 
-mod info;
+pub(crate) mod info;
 mod login;
+mod logout;
+mod migrate_keyring;
+mod status;
+mod switch;
+mod token;
 
 #[derive(Subcommand, Debug)]
 pub enum Auth {
     #[command(about = "Login to your Cartridge account.")]
     Login(LoginArgs),
+    #[command(about = "Remove the stored credentials of the active profile.")]
+    Logout(LogoutArgs),
     #[command(about = "Display info about the authenticated user.")]
     Info(InfoArgs),
+    #[command(about = "Show who is logged in and which endpoints are used.", aliases = ["whoami"])]
+    Status(StatusArgs),
+    #[command(about = "Authenticate with an API token, e.g. in CI.")]
+    Token(TokenArgs),
+    #[command(about = "Switch the active account profile.")]
+    Switch(SwitchArgs),
+    #[command(about = "Move stored credentials from the credentials file into the OS keychain.")]
+    MigrateKeyring(MigrateKeyringArgs),
 }
 
 impl Auth {
     pub async fn run(&self) -> Result<()> {
         match &self {
             Auth::Login(args) => args.run(),
+            Auth::Logout(args) => args.run(),
             Auth::Info(args) => args.run().await,
+            Auth::Status(args) => args.run().await,
+            Auth::Token(args) => args.run(),
+            Auth::Switch(args) => args.run(),
+            Auth::MigrateKeyring(args) => args.run(),
         }
     }
 }
@@ -0,0 +1,60 @@
+use std::env;
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use serde_json::json;
+
+use crate::{api::ApiClient, constant, credential, output};
+
+use super::info::{me::Variables, Me};
+
+#[derive(Debug, Args)]
+pub struct StatusArgs {
+    #[arg(long)]
+    #[arg(help = "Print the status as JSON.")]
+    pub json: bool,
+}
+
+impl StatusArgs {
+    pub async fn run(&self) -> Result<()> {
+        let profile = credential::active_profile()?;
+        let source = if env::var("SLOT_AUTH_TOKEN").is_ok_and(|t| !t.is_empty()) {
+            "SLOT_AUTH_TOKEN"
+        } else {
+            "stored credentials"
+        };
+
+        let client = ApiClient::new();
+        let data = client.query::<Me>(Variables {}).await?;
+
+        let me = data
+            .me
+            .ok_or_else(|| anyhow!("Not logged in. Login with `slot auth login`."))?;
+
+        if self.json || output::is_json() {
+            let status = json!({
+                "profile": profile,
+                "source": source,
+                "id": me.id,
+                "name": me.name,
+                "contract_address": me.contract_address,
+                "api_url": constant::api_url(),
+                "keychain_url": constant::keychain_url(),
+            });
+            output::print_json(&status)?;
+        } else {
+            println!("Profile: {profile}");
+            println!("Token: {source}");
+            println!("Username: {}", me.name.as_deref().unwrap_or(&me.id));
+            println!("Account: {}", me.id);
+            if let Some(address) = &me.contract_address {
+                println!("Address: {address}");
+            }
+            println!("\nEndpoints:");
+            println!("  API: {}", constant::api_url());
+            println!("  Keychain: {}", constant::keychain_url());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+use anyhow::Result;
+use clap::Args;
+use serde_json::json;
+
+use crate::{
+    credential::{self, Credentials},
+    output,
+};
+
+#[derive(Debug, Args)]
+pub struct SwitchArgs {
+    #[arg(help = "The profile to make active.")]
+    pub profile: String,
+}
+
+impl SwitchArgs {
+    pub fn run(&self) -> Result<()> {
+        Credentials::load_profile(&self.profile)?;
+        credential::set_active_profile(&self.profile)?;
+        if output::is_json() {
+            return output::print_json(&json!({ "profile": self.profile }));
+        }
+        println!("Switched to profile `{}`.", self.profile);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+use anyhow::Result;
+use clap::Args;
+use serde_json::json;
+
+use crate::{
+    credential::{self, Credentials},
+    output,
+};
+
+#[derive(Debug, Args)]
+pub struct TokenArgs {
+    #[arg(help = "The API token. CI runners can set `SLOT_AUTH_TOKEN` instead.")]
+    pub token: String,
+}
+
+impl TokenArgs {
+    pub fn run(&self) -> Result<()> {
+        Credentials::from_token(self.token.clone()).write()?;
+        if output::is_json() {
+            let profile = credential::active_profile()?;
+            return output::print_json(&json!({ "profile": profile, "logged_in": true }));
+        }
+        println!("Token stored. You are now logged in!");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use std::{env, fmt::Write as _, fs::OpenOptions, io::Write, path::PathBuf};
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::{
+    api::ApiClient,
+    command::deployments::{
+        describe::{self, describe_deployment::DescribeDeploymentDeploymentConfig},
+        manifest::Manifest,
+    },
+};
+
+use super::annotate_error;
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Annotate options")]
+pub struct AnnotateArgs {
+    #[arg(short, long, default_value = "slot.toml")]
+    #[arg(value_name = "path")]
+    #[arg(help = "The manifest whose deployments to summarize.")]
+    pub file: PathBuf,
+}
+
+impl AnnotateArgs {
+    /// Write a table of the manifest's endpoints to the job summary, or to stdout outside of
+    /// GitHub Actions, and flag deployments that can't be found.
+    pub async fn run(&self) -> Result<()> {
+        let manifest = Manifest::load(&self.file)?;
+        let client = ApiClient::new();
+
+        let mut summary = String::from("## Slot deployments\n\n");
+        summary.push_str("| Project | Service | Endpoints |\n| --- | --- | --- |\n");
+        for (project, service) in manifest.services() {
+            let name = format!("{service:?}").to_lowercase();
+            let endpoints = match describe::fetch(&client, &project, &service).await {
+                Ok(deployment) => match deployment.config {
+                    DescribeDeploymentDeploymentConfig::KatanaConfig(config) => config.rpc,
+                    DescribeDeploymentDeploymentConfig::ToriiConfig(config) => {
+                        format!("{}<br>{}", config.graphql, config.grpc)
+                    }
+                },
+                Err(e) => {
+                    annotate_error(&format!("{project} {name}: {e}"));
+                    format!("⚠️ {e}")
+                }
+            };
+            writeln!(summary, "| {project} | {name} | {endpoints} |")?;
+        }
+
+        match env::var_os("GITHUB_STEP_SUMMARY") {
+            Some(path) => {
+                let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+                file.write_all(summary.as_bytes())?;
+            }
+            None => print!("{summary}"),
+        }
+
+        Ok(())
+    }
+}
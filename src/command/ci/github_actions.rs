@@ -0,0 +1,89 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
+use serde_json::json;
+
+use crate::output;
+
+const WORKFLOW: &str = r#"name: Slot
+
+on:
+  push:
+    branches: [main]
+  workflow_dispatch:
+
+jobs:
+  deploy:
+    runs-on: ubuntu-latest
+    env:
+      # A Cartridge API token, stored as a repository secret.
+      SLOT_AUTH_TOKEN: ${{ secrets.SLOT_AUTH_TOKEN }}
+    steps:
+      - uses: actions/checkout@v4
+      - name: Install slot
+        run: |
+          curl -L https://slot.cartridge.sh | bash
+          ~/.slot/bin/slotup
+          echo "$HOME/.slot/bin" >> "$GITHUB_PATH"
+      - name: Deploy
+        run: slot deployments apply --file {manifest} --yes
+      - name: Summarize
+        if: always()
+        run: slot ci annotate --file {manifest}
+"#;
+
+#[derive(Subcommand, Debug)]
+pub enum GithubActions {
+    #[command(about = "Write a workflow that applies a manifest on every push to main.")]
+    Init(InitArgs),
+}
+
+impl GithubActions {
+    pub fn run(&self) -> Result<()> {
+        match &self {
+            GithubActions::Init(args) => args.run(),
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Init options")]
+pub struct InitArgs {
+    #[arg(long, default_value = ".github/workflows/slot.yml")]
+    #[arg(value_name = "path")]
+    #[arg(help = "Where to write the workflow.")]
+    pub path: PathBuf,
+
+    #[arg(long, default_value = "slot.toml")]
+    #[arg(value_name = "path")]
+    #[arg(help = "The manifest the workflow applies.")]
+    pub manifest: String,
+
+    #[arg(long)]
+    #[arg(help = "Overwrite the workflow if it already exists.")]
+    pub force: bool,
+}
+
+impl InitArgs {
+    pub fn run(&self) -> Result<()> {
+        if self.path.exists() && !self.force {
+            return Err(anyhow!(
+                "{} already exists. Pass `--force` to overwrite it.",
+                self.path.display()
+            ));
+        }
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, WORKFLOW.replace("{manifest}", &self.manifest))?;
+
+        if output::is_json() {
+            return output::print_json(&json!({ "path": self.path }));
+        }
+        println!("Wrote {}", self.path.display());
+        println!("\nStore a Cartridge API token as the SLOT_AUTH_TOKEN secret of the repository.");
+        Ok(())
+    }
+}
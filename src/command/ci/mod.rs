@@ -0,0 +1,47 @@
+use std::env;
+
+use anyhow::Result;
+use clap::Subcommand;
+
+use self::{annotate::AnnotateArgs, github_actions::GithubActions};
+
+mod annotate;
+mod github_actions;
+
+#[derive(Subcommand, Debug)]
+pub enum Ci {
+    #[command(subcommand)]
+    #[command(about = "Set up Slot in GitHub Actions.")]
+    GithubActions(GithubActions),
+    #[command(about = "Summarize a manifest's deployments for the CI job, e.g. their endpoints.")]
+    Annotate(AnnotateArgs),
+}
+
+/// Whether this is running as a GitHub Actions step.
+pub fn in_github_actions() -> bool {
+    env::var("GITHUB_ACTIONS").is_ok_and(|v| v == "true")
+}
+
+/// Surface an error as an annotation on the workflow run when inside GitHub Actions.
+pub fn annotate_error(message: &str) {
+    if in_github_actions() {
+        println!("::error title=slot::{}", escape(message));
+    }
+}
+
+/// Workflow commands end at a newline, so line breaks and `%` have to be encoded.
+fn escape(message: &str) -> String {
+    message
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+impl Ci {
+    pub async fn run(&self) -> Result<()> {
+        match &self {
+            Ci::GithubActions(cmd) => cmd.run(),
+            Ci::Annotate(args) => args.run().await,
+        }
+    }
+}
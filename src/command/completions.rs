@@ -0,0 +1,23 @@
+use std::io::{self, Write};
+
+use anyhow::Result;
+use clap::{Args, CommandFactory};
+use clap_complete::Shell;
+
+use crate::cli::Cli;
+
+#[derive(Debug, Args)]
+pub struct CompletionsArgs {
+    #[arg(help = "The shell to generate completions for.")]
+    pub shell: Shell,
+}
+
+impl CompletionsArgs {
+    pub fn run(&self) -> Result<()> {
+        // Buffer first: writing straight to stdout panics when the pipe is closed early.
+        let mut script = Vec::new();
+        clap_complete::generate(self.shell, &mut Cli::command(), "slot", &mut script);
+        io::stdout().write_all(&script)?;
+        Ok(())
+    }
+}
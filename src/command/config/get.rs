@@ -0,0 +1,27 @@
+use anyhow::Result;
+use clap::Args;
+
+use serde_json::json;
+
+use crate::{config::Config, output};
+
+#[derive(Debug, Args)]
+pub struct GetArgs {
+    #[arg(help = "The setting to print, e.g. `katana_version`.")]
+    pub key: String,
+}
+
+impl GetArgs {
+    pub fn run(&self) -> Result<()> {
+        let config = Config::load()?;
+        if output::is_json() {
+            return output::print_json(&json!({ &self.key: config.value(&self.key)? }));
+        }
+
+        if let Some(value) = config.value(&self.key)? {
+            println!("{value}");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+use anyhow::Result;
+use clap::Args;
+
+use serde_json::{Map, Value};
+
+use crate::{config::Config, output};
+
+#[derive(Debug, Args)]
+pub struct ListArgs {}
+
+impl ListArgs {
+    pub fn run(&self) -> Result<()> {
+        let config = Config::load()?;
+        if output::is_json() {
+            let mut settings = Map::new();
+            for key in Config::KEYS {
+                let value = config.value(key)?.map(|v| Value::String(v.to_string()));
+                settings.insert(key.to_string(), value.unwrap_or(Value::Null));
+            }
+            return output::print_json(&settings);
+        }
+
+        for key in Config::KEYS {
+            println!("{key} = {}", config.value(key)?.unwrap_or(""));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+use anyhow::Result;
+use clap::Subcommand;
+
+use self::{get::GetArgs, list::ListArgs, set::SetArgs};
+
+mod get;
+mod list;
+mod set;
+
+#[derive(Subcommand, Debug)]
+pub enum Config {
+    #[command(about = "Print the value of a setting.")]
+    Get(GetArgs),
+    #[command(about = "Change or clear a setting.")]
+    Set(SetArgs),
+    #[command(about = "List all settings.", aliases = ["ls"])]
+    List(ListArgs),
+}
+
+impl Config {
+    pub fn run(&self) -> Result<()> {
+        match &self {
+            Config::Get(args) => args.run(),
+            Config::Set(args) => args.run(),
+            Config::List(args) => args.run(),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use anyhow::Result;
+use clap::Args;
+
+use crate::config::Config;
+
+#[derive(Debug, Args)]
+pub struct SetArgs {
+    #[arg(help = "The setting to change, e.g. `katana_version`.")]
+    pub key: String,
+
+    #[arg(help = "The new value. Clears the setting when omitted.")]
+    pub value: Option<String>,
+}
+
+impl SetArgs {
+    pub fn run(&self) -> Result<()> {
+        let mut config = Config::load()?;
+        config.set(&self.key, self.value.clone())?;
+        config.write()?;
+
+        Ok(())
+    }
+}
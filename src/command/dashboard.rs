@@ -0,0 +1,233 @@
+use std::{
+    io::{self, Stdout},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use clap::Args;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame, Terminal,
+};
+
+use crate::{
+    api::ApiClient,
+    command::deployments::{
+        list::{self, Entry},
+        logs::{strip_ansi, LogReader},
+        services::Service,
+    },
+    output,
+};
+
+const LOG_LINES: i64 = 200;
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Dashboard options")]
+pub struct DashboardArgs {
+    #[arg(long, value_name = "team")]
+    #[arg(
+        help = "Only show deployments of this team. Defaults to .slot/project.toml, then `slot config`."
+    )]
+    pub team: Option<String>,
+
+    #[arg(long, default_value = "2")]
+    #[arg(value_name = "seconds")]
+    #[arg(help = "How often to refresh the logs of the selected deployment.")]
+    pub interval: u64,
+}
+
+struct App {
+    entries: Vec<Entry>,
+    selected: ListState,
+    logs: String,
+    error: Option<String>,
+}
+
+impl DashboardArgs {
+    pub async fn run(&self) -> Result<()> {
+        output::text_only("slot dashboard")?;
+        let client = ApiClient::new();
+        let team = list::team(&self.team)?;
+        let entries = list::fetch(&client, team.as_deref(), None).await?;
+
+        let mut terminal = TerminalGuard::new()?;
+        let mut app = App {
+            selected: ListState::default().with_selected((!entries.is_empty()).then_some(0)),
+            entries,
+            logs: String::new(),
+            error: None,
+        };
+
+        let interval = Duration::from_secs(self.interval);
+        let mut refreshed: Option<Instant> = None;
+        loop {
+            if !matches!(refreshed, Some(at) if at.elapsed() < interval) {
+                app.refresh_logs().await;
+                refreshed = Some(Instant::now());
+            }
+
+            terminal.0.draw(|frame| app.draw(frame))?;
+
+            if !event::poll(Duration::from_millis(100))? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => break,
+                // Raw mode turns Ctrl-C into a key press instead of a signal.
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+                KeyCode::Down | KeyCode::Char('j') => {
+                    app.select(1);
+                    refreshed = None;
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    app.select(-1);
+                    refreshed = None;
+                }
+                KeyCode::Char('r') => {
+                    match list::fetch(&client, team.as_deref(), None).await {
+                        Ok(entries) => {
+                            app.entries = entries;
+                            app.select(0);
+                        }
+                        Err(e) => app.error = Some(e.to_string()),
+                    }
+                    refreshed = None;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl App {
+    fn current(&self) -> Option<&Entry> {
+        self.selected.selected().and_then(|i| self.entries.get(i))
+    }
+
+    /// Move the selection by `step`, staying within the list.
+    fn select(&mut self, step: isize) {
+        if self.entries.is_empty() {
+            self.selected.select(None);
+            return;
+        }
+
+        let last = self.entries.len() - 1;
+        let current = self.selected.selected().unwrap_or(0).min(last);
+        self.selected
+            .select(Some(current.saturating_add_signed(step).min(last)));
+    }
+
+    async fn refresh_logs(&mut self) {
+        let Some(entry) = self.current() else {
+            self.logs.clear();
+            return;
+        };
+        let service = match entry.service.as_str() {
+            "katana" => Service::Katana,
+            "torii" => Service::Torii,
+            other => {
+                self.logs = format!("Logs are not available for {other} deployments.");
+                return;
+            }
+        };
+
+        let reader = LogReader::new(service, entry.project.clone());
+        match reader.query(None, LOG_LINES).await {
+            Ok(logs) => {
+                self.logs = logs
+                    .content
+                    .lines()
+                    .map(strip_ansi)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(frame.size());
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+            .split(rows[0]);
+
+        let items = self
+            .entries
+            .iter()
+            .map(|entry| ListItem::new(format!("{} {}", entry.project, entry.service)))
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Deployments"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, columns[0], &mut self.selected);
+
+        let title = match self.current() {
+            Some(entry) => format!("Logs: {} {}", entry.project, entry.service),
+            None => String::from("Logs"),
+        };
+        // Keep the newest lines in view.
+        let height = columns[1].height.saturating_sub(2) as usize;
+        let lines = self.logs.lines().collect::<Vec<_>>();
+        let tail = lines[lines.len().saturating_sub(height)..].join("\n");
+        let logs = Paragraph::new(tail).block(Block::default().borders(Borders::ALL).title(title));
+        frame.render_widget(logs, columns[1]);
+
+        let status = match &self.error {
+            Some(error) => format!("Error: {error}"),
+            None => String::from("↑/↓ select  r reload  q quit"),
+        };
+        frame.render_widget(Paragraph::new(status), rows[1]);
+    }
+}
+
+/// Puts the terminal back the way it was even if the dashboard exits early.
+struct TerminalGuard(Terminal<CrosstermBackend<Stdout>>);
+
+impl TerminalGuard {
+    fn new() -> Result<Self> {
+        enable_raw_mode()?;
+        let terminal = match Terminal::new(CrosstermBackend::new(io::stdout())) {
+            Ok(terminal) => terminal,
+            Err(e) => {
+                let _ = disable_raw_mode();
+                return Err(e.into());
+            }
+        };
+
+        // From here on, dropping the guard puts the terminal back however setup fails.
+        let mut guard = Self(terminal);
+        execute!(guard.0.backend_mut(), EnterAlternateScreen)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(self.0.backend_mut(), LeaveAlternateScreen);
+        let _ = self.0.show_cursor();
+    }
+}
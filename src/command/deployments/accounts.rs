@@ -0,0 +1,250 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use serde::Serialize;
+use toml_edit::{value, Document};
+
+use crate::{constant, output, storage::write_atomic};
+
+use super::{
+    logs::{overlap, strip_ansi, LogReader},
+    services::Service,
+};
+
+/// Katana prints its prefunded accounts once at startup, so read from the very beginning.
+const SINCE_START: &str = "1970-01-01T00:00:00Z";
+const LOG_LINES: i64 = 1000;
+/// How many pages to read looking for the startup banner before giving up.
+const MAX_PAGES: usize = 20;
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Accounts options")]
+pub struct AccountsArgs {
+    #[arg(help = "The project of the katana deployment.")]
+    pub project: String,
+
+    #[arg(long)]
+    #[arg(help = "Print the accounts as JSON.")]
+    pub json: bool,
+
+    #[arg(long, value_name = "path")]
+    #[arg(
+        help = "Write the RPC URL and an account into the [env] table of this Dojo profile, e.g. dojo_dev.toml."
+    )]
+    pub dojo_config: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "index",
+        default_value = "0",
+        requires = "dojo_config"
+    )]
+    #[arg(help = "Which account to write with `--dojo-config`.")]
+    pub index: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Account {
+    pub address: String,
+    pub private_key: String,
+    pub public_key: String,
+    pub class_hash: Option<String>,
+}
+
+impl AccountsArgs {
+    pub async fn run(&self) -> Result<()> {
+        let accounts = prefunded(&self.project).await?;
+
+        if let Some(path) = &self.dojo_config {
+            let account = accounts.get(self.index).ok_or_else(|| {
+                anyhow!(
+                    "There is no account {}, the deployment has {}.",
+                    self.index,
+                    accounts.len()
+                )
+            })?;
+            self.write_dojo_config(path, account)?;
+            println!("Wrote account {} to {}", self.index, path.display());
+            return Ok(());
+        }
+
+        if self.json || output::is_json() {
+            output::print_json(&accounts)?;
+            return Ok(());
+        }
+
+        for (i, account) in accounts.iter().enumerate() {
+            println!("Account #{i}");
+            println!("  Address: {}", account.address);
+            println!("  Private Key: {}", account.private_key);
+            println!("  Public Key: {}", account.public_key);
+            if let Some(class_hash) = &account.class_hash {
+                println!("  Class Hash: {class_hash}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Point the profile's `[env]` at this deployment, keeping the rest of the file as is.
+    fn write_dojo_config(&self, path: &Path, account: &Account) -> Result<()> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(anyhow!("Failed to read {}: {e}", path.display())),
+        };
+
+        let rpc_url = format!("{}/x/{}/katana", constant::api_url(), self.project);
+        let updated = update_dojo_config(&contents, &rpc_url, account)
+            .map_err(|e| anyhow!("Invalid Dojo config {}: {e}", path.display()))?;
+
+        // It now holds a private key, so keep it to its owner.
+        write_atomic(path, updated.as_bytes(), true)
+            .map_err(|e| anyhow!("Failed to write {}: {e}", path.display()))?;
+
+        Ok(())
+    }
+}
+
+/// Set the `[env]` keys that point a Dojo profile at the deployment, leaving everything
+/// else, comments and formatting included, untouched.
+fn update_dojo_config(contents: &str, rpc_url: &str, account: &Account) -> Result<String> {
+    let mut config = contents.parse::<Document>()?;
+    let env = config
+        .entry("env")
+        .or_insert(toml_edit::table())
+        .as_table_like_mut()
+        .ok_or_else(|| anyhow!("`env` is not a table."))?;
+
+    env.insert("rpc_url", value(rpc_url));
+    env.insert("account_address", value(&account.address));
+    env.insert("private_key", value(&account.private_key));
+
+    Ok(config.to_string())
+}
+
+/// The prefunded accounts of a katana deployment, as printed in its startup logs.
+pub(super) async fn prefunded(project: &str) -> Result<Vec<Account>> {
+    let reader = LogReader::new(Service::Katana, project.to_string());
+    let mut since = SINCE_START.to_string();
+    let mut lines: Vec<String> = Vec::new();
+    let mut banner = false;
+
+    // Walk forward from the start, and read one page past the banner in case it was split.
+    for pages in 1.. {
+        let logs = reader.page(since.clone(), LOG_LINES).await?;
+        let page: Vec<&str> = logs.content.lines().collect();
+        let new = &page[overlap(&lines, &page)..];
+        let done = banner || new.is_empty();
+        lines.extend(new.iter().map(|line| line.to_string()));
+        if done {
+            break;
+        }
+
+        banner = !parse_accounts(&lines.join("\n")).is_empty();
+        if !banner && pages == MAX_PAGES {
+            return Err(anyhow!(
+                "No prefunded accounts in the first {} lines of the katana logs of {project}. \
+                 The startup banner is outside the window that can be searched.",
+                lines.len()
+            ));
+        }
+        since = logs.until;
+    }
+
+    let accounts = parse_accounts(&lines.join("\n"));
+    if accounts.is_empty() {
+        return Err(anyhow!(
+            "No prefunded accounts found in the katana logs of {project}."
+        ));
+    }
+
+    Ok(accounts)
+}
+
+/// Pull the accounts out of katana's startup banner, where each one is printed as
+/// `| Account address | 0x...` followed by its private and public key rows.
+fn parse_accounts(content: &str) -> Vec<Account> {
+    let mut accounts: Vec<Account> = Vec::new();
+    let mut contract = String::new();
+    let mut class_hash = None;
+
+    for line in content.lines() {
+        let line = strip_ansi(line);
+        let Some(start) = line.find('|') else {
+            continue;
+        };
+        let mut columns = line[start..].split('|').skip(1).map(str::trim);
+        let (Some(key), Some(value)) = (columns.next(), columns.next()) else {
+            continue;
+        };
+
+        match key.to_ascii_lowercase().as_str() {
+            "account address" => {
+                // Katana prints the banner again on restart.
+                if accounts.iter().any(|account| account.address == value) {
+                    break;
+                }
+                accounts.push(Account {
+                    address: value.to_string(),
+                    ..Default::default()
+                });
+            }
+            "private key" => {
+                if let Some(account) = accounts.last_mut() {
+                    account.private_key = value.to_string();
+                }
+            }
+            "public key" => {
+                if let Some(account) = accounts.last_mut() {
+                    account.public_key = value.to_string();
+                }
+            }
+            "contract" => contract = value.to_ascii_lowercase(),
+            "class hash" if contract.contains("account") => class_hash = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    for account in &mut accounts {
+        account.class_hash = class_hash.clone();
+    }
+
+    accounts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dojo_config_updates_keep_comments_and_other_keys() {
+        let contents = "\
+# Local profile.
+[world]
+name = \"game\" # shown in the explorer
+
+[env]
+rpc_url = \"http://localhost:5050\"
+world_address = \"0x1\"
+";
+        let account = Account {
+            address: "0xa".to_string(),
+            private_key: "0xb".to_string(),
+            ..Default::default()
+        };
+
+        let updated = update_dojo_config(contents, "https://api/x/game/katana", &account).unwrap();
+
+        assert!(updated
+            .starts_with("# Local profile.\n[world]\nname = \"game\" # shown in the explorer\n"));
+        assert!(updated.contains("rpc_url = \"https://api/x/game/katana\""));
+        assert!(updated.contains("world_address = \"0x1\""));
+        assert!(updated.contains("account_address = \"0xa\""));
+        assert!(updated.contains("private_key = \"0xb\""));
+    }
+}
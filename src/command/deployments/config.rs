@@ -0,0 +1,224 @@
+use std::{env, fs, path::Path, process};
+
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
+use serde::Serialize;
+use starknet::core::types::FieldElement;
+
+use crate::{api::ApiClient, output, storage::TempFile};
+
+use super::{
+    describe::{
+        self,
+        describe_deployment::{DescribeDeploymentDeployment, DescribeDeploymentDeploymentConfig},
+    },
+    services::{KatanaCreateArgs, Service, ToriiCreateArgs, UpdateServiceCommands},
+    update::UpdateArgs,
+};
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    #[command(about = "Print the live configuration of a deployment as TOML.")]
+    Get(GetArgs),
+    #[command(about = "Edit the live configuration of a deployment in $EDITOR.")]
+    Edit(EditArgs),
+}
+
+impl ConfigCommands {
+    pub async fn run(&self) -> Result<()> {
+        match &self {
+            ConfigCommands::Get(args) => args.run().await,
+            ConfigCommands::Edit(args) => args.run().await,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Config get options")]
+pub struct GetArgs {
+    #[arg(help = "The name of the project.")]
+    pub project: String,
+
+    #[arg(help = "The name of the service.")]
+    pub service: Service,
+}
+
+impl GetArgs {
+    pub async fn run(&self) -> Result<()> {
+        let client = ApiClient::new();
+        let current = describe::fetch(&client, &self.project, &self.service).await?;
+        let config = LiveConfig::from(&current);
+        if output::is_json() {
+            return output::print_json(&config);
+        }
+        print!("{}", config.to_toml()?);
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Config edit options")]
+pub struct EditArgs {
+    #[arg(help = "The name of the project.")]
+    pub project: String,
+
+    #[arg(help = "The name of the service.")]
+    pub service: Service,
+
+    #[arg(short, long)]
+    #[arg(help = "Apply the edit without asking for confirmation.")]
+    pub yes: bool,
+}
+
+impl EditArgs {
+    pub async fn run(&self) -> Result<()> {
+        output::text_only("slot deployments config edit")?;
+        let client = ApiClient::new();
+        let current = describe::fetch(&client, &self.project, &self.service).await?;
+        let original = LiveConfig::from(&current);
+        let contents = original.to_toml()?;
+
+        // Removed when it goes out of scope, whichever way this returns.
+        let file = TempFile::new(".toml", contents.as_bytes())
+            .map_err(|e| anyhow!("Failed to create a file to edit: {e}"))?;
+        let path = file.path();
+        open_editor(path)?;
+        let edited = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read {}: {e}", path.display()))?;
+        drop(file);
+
+        if edited == contents {
+            println!("No changes to apply.");
+            return Ok(());
+        }
+        reject_removed_keys(&contents, &edited)?;
+
+        let update_commands = match &original {
+            LiveConfig::Katana(original) => {
+                let edited: KatanaCreateArgs =
+                    toml::from_str(&edited).map_err(|e| anyhow!("Invalid katana config: {e}"))?;
+                warn_fixed("Accounts", &original.accounts, &edited.accounts);
+                warn_fixed("Chain ID", &original.chain_id, &edited.chain_id);
+                warn_fixed("Seed", &original.seed, &edited.seed);
+                UpdateServiceCommands::Katana((&edited).into())
+            }
+            LiveConfig::Torii(original) => {
+                let edited: ToriiCreateArgs =
+                    toml::from_str(&edited).map_err(|e| anyhow!("Invalid torii config: {e}"))?;
+                warn_fixed("RPC", &original.rpc, &edited.rpc);
+                warn_fixed("World", &original.world, &edited.world);
+                warn_fixed("Start Block", &original.start_block, &edited.start_block);
+                UpdateServiceCommands::Torii((&edited).into())
+            }
+        };
+
+        UpdateArgs {
+            project: self.project.clone(),
+            tier: None,
+            yes: self.yes,
+            update_commands,
+        }
+        .run()
+        .await
+    }
+}
+
+/// A deployment's configuration in the shape of the create `--config` files, so a
+/// fetched config can be edited and read back with the same keys.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum LiveConfig {
+    Katana(KatanaCreateArgs),
+    Torii(ToriiCreateArgs),
+}
+
+impl LiveConfig {
+    fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string(self)?)
+    }
+}
+
+impl From<&DescribeDeploymentDeployment> for LiveConfig {
+    fn from(deployment: &DescribeDeploymentDeployment) -> Self {
+        match &deployment.config {
+            DescribeDeploymentDeploymentConfig::KatanaConfig(config) => {
+                LiveConfig::Katana(KatanaCreateArgs {
+                    version: Some(config.version.clone()),
+                    block_time: config.block_time,
+                    fork_rpc_url: config.fork_rpc_url.clone(),
+                    fork_block_number: config.fork_block_number,
+                    accounts: config.accounts,
+                    invoke_max_steps: config.invoke_max_steps,
+                    validate_max_steps: config.validate_max_steps,
+                    disable_fee: config.disable_fee,
+                    gas_price: config.gas_price,
+                    chain_id: config.chain_id.clone(),
+                    ..Default::default()
+                })
+            }
+            DescribeDeploymentDeploymentConfig::ToriiConfig(config) => {
+                LiveConfig::Torii(ToriiCreateArgs {
+                    version: Some(config.version.clone()),
+                    rpc: Some(config.rpc.clone()),
+                    world: FieldElement::from_hex_be(&config.world).ok(),
+                    start_block: i64::try_from(config.start_block).ok(),
+                    ..Default::default()
+                })
+            }
+        }
+    }
+}
+
+/// Open `path` in the user's editor and wait for it to exit.
+fn open_editor(path: &Path) -> Result<()> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| String::from("vi"));
+
+    // Editors are often configured with arguments, e.g. `code --wait`.
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = process::Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .map_err(|e| anyhow!("Failed to launch editor `{editor}`: {e}"))?;
+
+    if !status.success() {
+        return Err(anyhow!(
+            "Editor `{editor}` exited with {status}, nothing was changed."
+        ));
+    }
+
+    Ok(())
+}
+
+/// Updates only send the keys that are present, so a key deleted in the editor would keep its
+/// current value. Refuse the edit rather than apply something other than what the file says.
+fn reject_removed_keys(original: &str, edited: &str) -> Result<()> {
+    let original: toml::Table = toml::from_str(original)?;
+    let edited: toml::Table = toml::from_str(edited).map_err(|e| anyhow!("Invalid config: {e}"))?;
+    let removed: Vec<&str> = original
+        .keys()
+        .filter(|key| !edited.contains_key(*key))
+        .map(String::as_str)
+        .collect();
+
+    if removed.is_empty() {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "Removing a key doesn't unset it, so the edit was not applied. Put back: {}.",
+        removed.join(", ")
+    ))
+}
+
+/// Some options are fixed once the deployment exists; say so rather than dropping the edit silently.
+fn warn_fixed<T: PartialEq>(label: &str, original: &Option<T>, edited: &Option<T>) {
+    if edited.is_some() && edited != original {
+        eprintln!(
+            "Warning: {label} can only be set when creating a deployment and will not change."
+        );
+    }
+}
@@ -1,25 +1,35 @@
 #![allow(clippy::enum_variant_names)]
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use clap::Args;
-use graphql_client::{GraphQLQuery, Response};
+use graphql_client::GraphQLQuery;
+use serde_json::{json, Value};
 
 use crate::{
     api::ApiClient,
     command::deployments::create::create_deployment::{
-        CreateDeploymentCreateDeployment::{KatanaConfig, ToriiConfig},
+        CreateDeploymentCreateDeployment::{self, KatanaConfig, ToriiConfig},
         CreateKatanaConfigInput, CreateServiceConfigInput, CreateServiceInput,
         CreateToriiConfigInput, DeploymentService, DeploymentTier, Variables,
     },
+    config::Config,
+    output,
 };
 
-use super::{services::CreateServiceCommands, Long, Tier};
+use super::{
+    services::{CreateServiceCommands, Service},
+    wait::WaitArgs,
+    Long, Tier,
+};
+
+/// Seconds between checks while `--verify` waits for torii to index the world.
+const VERIFY_INTERVAL: u64 = 5;
 
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "schema.json",
     query_path = "src/command/deployments/create.graphql",
-    response_derives = "Debug"
+    response_derives = "Debug, Serialize"
 )]
 pub struct CreateDeployment;
 
@@ -34,98 +44,165 @@ pub struct CreateArgs {
     pub tier: Tier,
 
     #[command(subcommand)]
-    create_commands: CreateServiceCommands,
+    pub create_commands: CreateServiceCommands,
 }
 
 impl CreateArgs {
     pub async fn run(&self) -> Result<()> {
-        let service = match &self.create_commands {
-            CreateServiceCommands::Katana(config) => CreateServiceInput {
-                type_: DeploymentService::katana,
-                version: config.version.clone(),
-                config: Some(CreateServiceConfigInput {
-                    katana: Some(CreateKatanaConfigInput {
-                        block_time: config.block_time,
-                        fork_rpc_url: config.fork_rpc_url.clone(),
-                        fork_block_number: config.fork_block_number,
-                        seed: Some(match &config.seed {
-                            Some(seed) => seed.clone(),
-                            None => rand::random::<u64>().to_string(),
-                        }),
-                        accounts: config.accounts,
-                        disable_fee: config.disable_fee,
-                        gas_price: config.gas_price,
-                        invoke_max_steps: config.invoke_max_steps,
-                        validate_max_steps: config.validate_max_steps,
-                        chain_id: config.chain_id.clone(),
-                    }),
-                    torii: None,
-                }),
-            },
-            CreateServiceCommands::Torii(config) => CreateServiceInput {
-                type_: DeploymentService::torii,
-                version: config.version.clone(),
-                config: Some(CreateServiceConfigInput {
-                    katana: None,
-                    torii: Some(CreateToriiConfigInput {
-                        rpc: config.rpc.clone(),
-                        world: format!("{:#x}", config.world),
-                        start_block: Some(config.start_block),
-                    }),
-                }),
-            },
-        };
+        if output::is_json() {
+            let (result, verified) = self.create_json().await?;
+            output::print_json(&result)?;
+            return verified;
+        }
 
-        let tier = match &self.tier {
-            Tier::Basic => DeploymentTier::basic,
-        };
+        let config = self.create().await?;
+        println!("Deployment success 🚀");
+        match config {
+            ToriiConfig(config) => {
+                println!("\nConfiguration:");
+                println!("  World: {}", config.world);
+                println!("  RPC: {}", config.rpc);
+                println!("  Start Block: {}", config.start_block);
+                println!("\nEndpoints:");
+                println!("  GRAPHQL: {}", config.graphql);
+                println!("  GRPC: {}", config.grpc);
+            }
+            KatanaConfig(config) => {
+                println!("\nEndpoints:");
+                println!("  RPC: {}", config.rpc);
+            }
+        }
 
-        let request_body = CreateDeployment::build_query(Variables {
-            project: self.project.clone(),
-            tier,
-            service,
-            wait: Some(true),
+        println!(
+            "\nStream logs with `slot deployments logs {} {} -f`",
+            self.project,
+            self.service_name()
+        );
+
+        if let Some(wait) = self.verify() {
+            println!();
+            wait.wait(false).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Create the deployment and report it as one JSON object, with the `--verify` result in
+    /// it rather than after it. A failed verification still returns the object, next to
+    /// the error.
+    pub(super) async fn create_json(&self) -> Result<(Value, Result<()>)> {
+        let config = self.create().await?;
+        let mut result = json!({
+            "project": self.project,
+            "service": self.service_name(),
+            "config": config,
         });
 
-        let client = ApiClient::new();
-        let res: Response<create_deployment::ResponseData> = client.post(&request_body).await?;
-        if let Some(errors) = res.errors.clone() {
-            for err in errors {
-                println!("Error: {}", err.message);
+        let verified = match self.verify() {
+            Some(wait) => {
+                let verified = wait.wait(true).await;
+                result["verify"] = match &verified {
+                    Ok(endpoint) => json!({ "healthy": true, "endpoint": endpoint }),
+                    Err(e) => json!({ "healthy": false, "error": e.to_string() }),
+                };
+                verified.map(|_| ())
             }
+            None => Ok(()),
+        };
+
+        Ok((result, verified))
+    }
 
-            return Ok(());
+    fn service_name(&self) -> &'static str {
+        match &self.create_commands {
+            CreateServiceCommands::Katana(_) => "katana",
+            CreateServiceCommands::Torii(_) => "torii",
         }
+    }
 
-        if let Some(data) = res.data {
-            println!("Deployment success 🚀");
-            match data.create_deployment {
-                ToriiConfig(config) => {
-                    println!("\nConfiguration:");
-                    println!("  World: {}", config.world);
-                    println!("  RPC: {}", config.rpc);
-                    println!("  Start Block: {}", config.start_block);
-                    println!("\nEndpoints:");
-                    println!("  GRAPHQL: {}", config.graphql);
-                    println!("  GRPC: {}", config.grpc);
-                }
-                KatanaConfig(config) => {
-                    println!("\nEndpoints:");
-                    println!("  RPC: {}", config.rpc);
-                }
-            }
+    fn verify(&self) -> Option<WaitArgs> {
+        match &self.create_commands {
+            CreateServiceCommands::Torii(config) if config.verify => Some(WaitArgs {
+                project: self.project.clone(),
+                service: Service::Torii,
+                timeout: config.verify_timeout,
+                interval: VERIFY_INTERVAL,
+                indexed: true,
+            }),
+            _ => None,
         }
+    }
 
+    async fn create(&self) -> Result<CreateDeploymentCreateDeployment> {
         let service = match &self.create_commands {
-            CreateServiceCommands::Katana(_) => "katana",
-            CreateServiceCommands::Torii(_) => "torii",
+            CreateServiceCommands::Katana(config) => {
+                let config = config.resolve()?;
+
+                CreateServiceInput {
+                    type_: DeploymentService::katana,
+                    version: config
+                        .version
+                        .clone()
+                        .or_else(|| Config::get().katana_version.clone()),
+                    config: Some(CreateServiceConfigInput {
+                        katana: Some(CreateKatanaConfigInput {
+                            block_time: config.block_time,
+                            fork_rpc_url: config.fork_rpc_url.clone(),
+                            fork_block_number: config.fork_block_number,
+                            seed: Some(match &config.seed {
+                                Some(seed) => seed.clone(),
+                                None => rand::random::<u64>().to_string(),
+                            }),
+                            accounts: config.accounts,
+                            disable_fee: config.disable_fee,
+                            gas_price: config.gas_price,
+                            invoke_max_steps: config.invoke_max_steps,
+                            validate_max_steps: config.validate_max_steps,
+                            chain_id: config.chain_id.clone(),
+                        }),
+                        torii: None,
+                    }),
+                }
+            }
+            CreateServiceCommands::Torii(config) => {
+                let config = config.resolve()?;
+                let rpc = config.rpc.clone().ok_or_else(|| {
+                    anyhow!("Missing `--rpc`, pass it or set `rpc` in the config file.")
+                })?;
+                let world = config.world.ok_or_else(|| {
+                    anyhow!("Missing `--world`, pass it or set `world` in the config file.")
+                })?;
+
+                CreateServiceInput {
+                    type_: DeploymentService::torii,
+                    version: config
+                        .version
+                        .clone()
+                        .or_else(|| Config::get().torii_version.clone()),
+                    config: Some(CreateServiceConfigInput {
+                        katana: None,
+                        torii: Some(CreateToriiConfigInput {
+                            rpc,
+                            world: format!("{:#x}", world),
+                            start_block: config.start_block,
+                        }),
+                    }),
+                }
+            }
         };
 
-        println!(
-            "\nStream logs with `slot deployments logs {} {service} -f`",
-            self.project
-        );
+        let tier: DeploymentTier = self.tier.to_api();
 
-        Ok(())
+        let client = ApiClient::new();
+        let data = client
+            .query::<CreateDeployment>(Variables {
+                project: self.project.clone(),
+                tier,
+                service,
+                wait: Some(true),
+            })
+            .await?;
+
+        Ok(data.create_deployment)
     }
 }
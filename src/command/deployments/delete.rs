@@ -2,13 +2,17 @@
 
 use anyhow::Result;
 use clap::Args;
-use graphql_client::{GraphQLQuery, Response};
+use graphql_client::GraphQLQuery;
+use serde_json::json;
 
 use crate::{
     api::ApiClient,
     command::deployments::delete::delete_deployment::{DeploymentService, Variables},
+    output, prompt,
 };
 
+use super::services::Service;
+
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "schema.json",
@@ -17,12 +21,6 @@ use crate::{
 )]
 pub struct DeleteDeployment;
 
-#[derive(clap::ValueEnum, Clone, Debug, serde::Serialize)]
-pub enum Service {
-    Katana,
-    Torii,
-}
-
 #[derive(Debug, Args)]
 #[command(next_help_heading = "Delete options")]
 pub struct DeleteArgs {
@@ -31,32 +29,57 @@ pub struct DeleteArgs {
 
     #[arg(help = "The name of the service.")]
     pub service: Service,
+
+    #[arg(short, long)]
+    #[arg(help = "Delete without asking for confirmation.")]
+    pub force: bool,
 }
 
 impl DeleteArgs {
     pub async fn run(&self) -> Result<()> {
-        let service = match &self.service {
-            Service::Katana => DeploymentService::katana,
-            Service::Torii => DeploymentService::torii,
-        };
-
-        let request_body = DeleteDeployment::build_query(Variables {
-            project: self.project.clone(),
-            service,
-        });
-
-        let client = ApiClient::new();
-        let res: Response<delete_deployment::ResponseData> = client.post(&request_body).await?;
-        if let Some(errors) = res.errors.clone() {
-            for err in errors {
-                println!("Error: {}", err.message);
+        output::require_confirmed(self.force, "--force")?;
+        if !self.force {
+            println!(
+                "This permanently deletes the {} service of {}.",
+                format!("{:?}", self.service).to_lowercase(),
+                self.project
+            );
+            let answer = prompt::input("Type the project name to confirm:")?;
+            if answer != self.project {
+                println!("Delete cancelled.");
+                return Ok(());
             }
         }
 
-        if res.data.is_some() {
+        delete(&ApiClient::new(), &self.project, &self.service).await?;
+        if output::is_json() {
+            let service = format!("{:?}", self.service).to_lowercase();
+            output::print_json(&json!({
+                "project": self.project,
+                "service": service,
+                "deleted": true,
+            }))?;
+        } else {
             println!("Delete success 🚀");
         }
 
         Ok(())
     }
 }
+
+/// Delete a deployment, failing if the API reports an error.
+pub(crate) async fn delete(client: &ApiClient, project: &str, service: &Service) -> Result<()> {
+    let service = match service {
+        Service::Katana => DeploymentService::katana,
+        Service::Torii => DeploymentService::torii,
+    };
+
+    client
+        .query::<DeleteDeployment>(Variables {
+            project: project.to_string(),
+            service,
+        })
+        .await?;
+
+    Ok(())
+}
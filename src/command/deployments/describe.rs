@@ -1,88 +1,158 @@
 #![allow(clippy::enum_variant_names)]
 
-use anyhow::Result;
+use std::{fmt::Write, time::Duration};
+
+use anyhow::{anyhow, Result};
 use clap::Args;
-use graphql_client::{GraphQLQuery, Response};
+use graphql_client::GraphQLQuery;
 
-use crate::api::ApiClient;
+use crate::{api::ApiClient, cache, output, project};
 
 use self::describe_deployment::{
-    DeploymentService,
+    DeploymentService, DescribeDeploymentDeployment,
     DescribeDeploymentDeploymentConfig::{KatanaConfig, ToriiConfig},
-    ResponseData, Variables,
+    Variables,
 };
 
-use super::services::Service;
+use super::{services::Service, watch::watch};
 
 type Long = u64;
+type Time = String;
 
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "schema.json",
     query_path = "src/command/deployments/describe.graphql",
-    response_derives = "Debug"
+    response_derives = "Debug, Serialize"
 )]
 pub struct DescribeDeployment;
 
 #[derive(Debug, Args)]
 #[command(next_help_heading = "Describe options")]
 pub struct DescribeArgs {
-    #[arg(help = "The project of the project.")]
-    pub project: String,
+    #[arg(help = "The project of the project. Defaults to the one in .slot/project.toml.")]
+    pub project: Option<String>,
+
+    #[arg(help = "The service of the project. Defaults to the one in .slot/project.toml.")]
+    pub service: Option<Service>,
+
+    #[arg(long)]
+    #[arg(help = "Print the deployment as JSON.")]
+    pub json: bool,
 
-    #[arg(help = "The service of the project.")]
-    pub service: Service,
+    #[arg(short, long)]
+    #[arg(help = "Keep refreshing the output, highlighting what changed.")]
+    pub watch: bool,
+
+    #[arg(long, default_value = "2", requires = "watch")]
+    #[arg(value_name = "seconds")]
+    #[arg(help = "How often `--watch` refreshes.")]
+    pub interval: u64,
+
+    #[arg(long, default_value = "0", value_name = "seconds")]
+    #[arg(help = "Reuse a cached response up to this old instead of querying the API.")]
+    pub max_age: u64,
 }
 
 impl DescribeArgs {
     pub async fn run(&self) -> Result<()> {
-        let service = match self.service {
-            Service::Torii => DeploymentService::torii,
-            Service::Katana => DeploymentService::katana,
-        };
+        let client = ApiClient::new();
+        if self.watch {
+            let interval = Duration::from_secs(self.interval);
+            return watch(interval, || self.render(&client)).await;
+        }
 
-        let request_body = DescribeDeployment::build_query(Variables {
-            project: self.project.clone(),
-            service,
-        });
+        print!("{}", self.render(&client).await?);
+        Ok(())
+    }
 
-        let client = ApiClient::new();
-        let res: Response<ResponseData> = client.post(&request_body).await?;
-        if let Some(errors) = res.errors.clone() {
-            for err in errors {
-                println!("Error: {}", err.message);
-            }
+    async fn render(&self, client: &ApiClient) -> Result<String> {
+        let (project, service) = project::deployment(&self.project, &self.service)?;
+        let key = format!("describe-{project}-{service:?}");
+        let deployment = cache::fetch_or_cached(
+            &key,
+            Duration::from_secs(self.max_age),
+            fetch(client, &project, &service),
+        )
+        .await?;
+        if self.json || output::is_json() {
+            return Ok(serde_json::to_string_pretty(&deployment)? + "\n");
         }
 
-        if let Some(data) = res.data {
-            if let Some(deployment) = data.deployment {
-                println!("Project: {}", deployment.project);
-                println!(
-                    "Branch: {}",
-                    deployment.branch.unwrap_or_else(|| String::from("Default"))
-                );
-                println!("Tier: {:?}", deployment.tier);
-
-                match deployment.config {
-                    ToriiConfig(config) => {
-                        println!("\nConfiguration:");
-                        println!("  Version: {}", config.version);
-                        println!("  World: {}", config.world);
-                        println!("  RPC: {}", config.rpc);
-                        println!("  Start Block: {}", config.start_block);
-                        println!("\nEndpoints:");
-                        println!("  GraphQL: {}", config.graphql);
-                        println!("  GRPC: {}", config.grpc);
-                    }
-                    KatanaConfig(config) => {
-                        println!("\nEndpoints:");
-                        println!("  Version: {}", config.version);
-                        println!("  RPC: {}", config.rpc);
-                    }
-                }
+        let mut out = String::new();
+        writeln!(out, "Project: {}", deployment.project)?;
+        writeln!(
+            out,
+            "Branch: {}",
+            deployment.branch.unwrap_or_else(|| String::from("Default"))
+        )?;
+        writeln!(out, "Service: {}", deployment.service.id)?;
+        writeln!(out, "Tier: {:?}", deployment.tier)?;
+        writeln!(out, "Auto Upgrade: {}", deployment.auto_upgrade)?;
+        writeln!(out, "Created: {}", deployment.created_at)?;
+        writeln!(out, "Updated: {}", deployment.updated_at)?;
+
+        match deployment.config {
+            ToriiConfig(config) => {
+                writeln!(out, "\nConfiguration:")?;
+                writeln!(out, "  Version: {}", config.version)?;
+                writeln!(out, "  World: {}", config.world)?;
+                writeln!(out, "  RPC: {}", config.rpc)?;
+                writeln!(out, "  Start Block: {}", config.start_block)?;
+                writeln!(out, "\nEndpoints:")?;
+                writeln!(out, "  GraphQL: {}", config.graphql)?;
+                writeln!(out, "  GRPC: {}", config.grpc)?;
+            }
+            KatanaConfig(config) => {
+                writeln!(out, "\nConfiguration:")?;
+                writeln!(out, "  Version: {}", config.version)?;
+                write_optional(&mut out, "Block Time", config.block_time)?;
+                write_optional(&mut out, "Fork RPC URL", config.fork_rpc_url)?;
+                write_optional(&mut out, "Fork Block Number", config.fork_block_number)?;
+                write_optional(&mut out, "Accounts", config.accounts)?;
+                write_optional(&mut out, "Invoke Max Steps", config.invoke_max_steps)?;
+                write_optional(&mut out, "Validate Max Steps", config.validate_max_steps)?;
+                write_optional(&mut out, "Disable Fee", config.disable_fee)?;
+                write_optional(&mut out, "Gas Price", config.gas_price)?;
+                write_optional(&mut out, "Chain ID", config.chain_id)?;
+                writeln!(out, "\nEndpoints:")?;
+                writeln!(out, "  RPC: {}", config.rpc)?;
             }
         }
 
-        Ok(())
+        Ok(out)
+    }
+}
+
+/// Fetch a single deployment, failing if the API reports an error or doesn't know it.
+pub(crate) async fn fetch(
+    client: &ApiClient,
+    project: &str,
+    service: &Service,
+) -> Result<DescribeDeploymentDeployment> {
+    let service = match service {
+        Service::Torii => DeploymentService::torii,
+        Service::Katana => DeploymentService::katana,
+    };
+
+    let data = client
+        .query::<DescribeDeployment>(Variables {
+            project: project.to_string(),
+            service,
+        })
+        .await?;
+
+    data.deployment
+        .ok_or_else(|| anyhow!("Deployment {project} not found."))
+}
+
+fn write_optional<T: std::fmt::Display>(
+    out: &mut String,
+    label: &str,
+    value: Option<T>,
+) -> std::fmt::Result {
+    match value {
+        Some(value) => writeln!(out, "  {label}: {value}"),
+        None => Ok(()),
     }
 }
@@ -0,0 +1,103 @@
+use anyhow::{anyhow, Result};
+use clap::Args;
+use serde_json::json;
+use starknet::{
+    accounts::{Account, Call, ExecutionEncoding, SingleOwnerAccount},
+    core::{
+        types::{BlockId, BlockTag, ContractClass, FieldElement},
+        utils::get_selector_from_name,
+    },
+    providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider},
+    signers::{LocalWallet, SigningKey},
+};
+use url::Url;
+
+use crate::{constant, output};
+
+use super::accounts::prefunded;
+
+/// The ETH contract katana deploys at genesis and charges fees in.
+const FEE_TOKEN: &str = "0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7";
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Faucet options")]
+pub struct FaucetArgs {
+    #[arg(help = "The project of the katana deployment.")]
+    pub project: String,
+
+    #[arg(help = "The address to fund.")]
+    pub address: FieldElement,
+
+    #[arg(long, default_value = "1000000000000000000", value_name = "amount")]
+    #[arg(help = "Amount to send, in the token's smallest unit (wei for ETH).")]
+    pub amount: u128,
+
+    #[arg(long, default_value = FEE_TOKEN, value_name = "address")]
+    #[arg(help = "ERC-20 token to send, katana's fee token by default.")]
+    pub token: FieldElement,
+
+    #[arg(long, default_value = "0", value_name = "index")]
+    #[arg(help = "Which prefunded account to send from.")]
+    pub index: usize,
+}
+
+impl FaucetArgs {
+    pub async fn run(&self) -> Result<()> {
+        let accounts = prefunded(&self.project).await?;
+        let funder = accounts.get(self.index).ok_or_else(|| {
+            anyhow!(
+                "There is no account {}, the deployment has {}.",
+                self.index,
+                accounts.len()
+            )
+        })?;
+        let address = FieldElement::from_hex_be(&funder.address)?;
+        let private_key = FieldElement::from_hex_be(&funder.private_key)?;
+
+        let rpc_url = format!("{}/x/{}/katana", constant::api_url(), self.project);
+        let provider = JsonRpcClient::new(HttpTransport::new(Url::parse(&rpc_url)?));
+        let chain_id = provider.chain_id().await?;
+
+        // Older katana versions prefund Cairo 0 accounts, which expect the legacy calldata.
+        let encoding = match provider
+            .get_class_at(BlockId::Tag(BlockTag::Latest), address)
+            .await?
+        {
+            ContractClass::Legacy(_) => ExecutionEncoding::Legacy,
+            ContractClass::Sierra(_) => ExecutionEncoding::New,
+        };
+
+        let signer = LocalWallet::from_signing_key(SigningKey::from_secret_scalar(private_key));
+        let account = SingleOwnerAccount::new(provider, signer, address, chain_id, encoding);
+
+        // `transfer(recipient, amount: u256)`, with the u256 as its low and high halves.
+        let result = account
+            .execute(vec![Call {
+                to: self.token,
+                selector: get_selector_from_name("transfer")?,
+                calldata: vec![self.address, self.amount.into(), FieldElement::ZERO],
+            }])
+            .send()
+            .await?;
+
+        if output::is_json() {
+            output::print_json(&json!({
+                "project": self.project,
+                "to": format!("{:#x}", self.address),
+                "from": funder.address,
+                "token": format!("{:#x}", self.token),
+                "amount": self.amount.to_string(),
+                "transaction_hash": format!("{:#x}", result.transaction_hash),
+            }))?;
+            return Ok(());
+        }
+
+        println!(
+            "Sent {} to {:#x} from account #{} 🚀",
+            self.amount, self.address, self.index
+        );
+        println!("\nTransaction: {:#x}", result.transaction_hash);
+
+        Ok(())
+    }
+}
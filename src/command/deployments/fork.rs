@@ -2,7 +2,8 @@
 
 use anyhow::Result;
 use clap::Args;
-use graphql_client::{GraphQLQuery, Response};
+use graphql_client::GraphQLQuery;
+use serde_json::json;
 use starknet::providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
 use url::Url;
 
@@ -11,6 +12,7 @@ use crate::{
     command::deployments::fork::fork_deployment::{
         DeploymentTier, ForkDeploymentForkDeployment::KatanaConfig, Variables,
     },
+    constant, output,
 };
 
 use super::{services::ForkServiceCommands, Long, Tier};
@@ -19,7 +21,7 @@ use super::{services::ForkServiceCommands, Long, Tier};
 #[graphql(
     schema_path = "schema.json",
     query_path = "src/command/deployments/fork.graphql",
-    response_derives = "Debug"
+    response_derives = "Debug, Serialize"
 )]
 pub struct ForkDeployment;
 
@@ -41,36 +43,37 @@ impl ForkArgs {
     pub async fn run(&self) -> Result<()> {
         let (fork_name, fork_block_number) = self.fork_config().await?;
 
-        let tier = match &self.tier {
-            Tier::Basic => DeploymentTier::basic,
-        };
-
-        let request_body = ForkDeployment::build_query(Variables {
-            project: self.project.clone(),
-            fork_name: fork_name.clone(),
-            fork_block_number,
-            tier,
-            wait: Some(true),
-        });
+        let tier: DeploymentTier = self.tier.to_api();
 
         let client = ApiClient::new();
-        let res: Response<fork_deployment::ResponseData> = client.post(&request_body).await?;
-        if let Some(errors) = res.errors.clone() {
-            for err in errors {
-                println!("Error: {}", err.message);
-            }
+        let data = client
+            .query::<ForkDeployment>(Variables {
+                project: self.project.clone(),
+                fork_name: fork_name.clone(),
+                fork_block_number,
+                tier,
+                wait: Some(true),
+            })
+            .await?;
+
+        if output::is_json() {
+            output::print_json(&json!({
+                "project": fork_name,
+                "forked_from": self.project,
+                "fork_block_number": fork_block_number,
+                "config": data.fork_deployment,
+            }))?;
+            return Ok(());
         }
 
-        if let Some(data) = res.data {
-            println!("Fork success 🚀");
-            if let KatanaConfig(config) = data.fork_deployment {
-                println!("\nEndpoints:");
-                println!("  RPC: {}", config.rpc);
-                println!(
-                    "\nStream logs with `slot deployments logs {} katana -f`",
-                    fork_name
-                );
-            }
+        println!("Fork success 🚀");
+        if let KatanaConfig(config) = data.fork_deployment {
+            println!("\nEndpoints:");
+            println!("  RPC: {}", config.rpc);
+            println!(
+                "\nStream logs with `slot deployments logs {} katana -f`",
+                fork_name
+            );
         }
 
         Ok(())
@@ -83,10 +86,8 @@ impl ForkArgs {
                     block_number
                 } else {
                     // Workaround to get latest block number. Perhaps Katana could default to latest if none is supplied
-                    let rpc_client = JsonRpcClient::new(HttpTransport::new(Url::parse(&format!(
-                        "https://api.cartridge.gg/x/{}/katana",
-                        self.project
-                    ))?));
+                    let rpc_url = format!("{}/x/{}/katana", constant::api_url(), self.project);
+                    let rpc_client = JsonRpcClient::new(HttpTransport::new(Url::parse(&rpc_url)?));
                     rpc_client.block_number().await?
                 };
 
@@ -0,0 +1,252 @@
+use std::{net::TcpListener, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    Router,
+};
+use clap::Args;
+use serde_json::json;
+
+use crate::{api::ApiClient, credential::Credentials, output};
+
+use super::{
+    describe::{self, describe_deployment::DescribeDeploymentDeploymentConfig},
+    services::Service,
+};
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Forward options")]
+pub struct ForwardArgs {
+    #[arg(help = "The name of the project.")]
+    pub project: String,
+
+    #[arg(help = "The name of the service.")]
+    pub service: Service,
+
+    #[arg(long, default_value = "5050")]
+    #[arg(value_name = "port")]
+    #[arg(help = "Local port to listen on. Only connections from this machine are accepted.")]
+    pub local_port: u16,
+}
+
+struct Proxy {
+    client: reqwest::Client,
+    target: String,
+    authorization: HeaderValue,
+    port: u16,
+}
+
+/// Headers that only describe the connection they arrived on, per RFC 9110.
+const HOP_BY_HOP: [header::HeaderName; 7] = [
+    header::CONNECTION,
+    header::PROXY_AUTHENTICATE,
+    header::PROXY_AUTHORIZATION,
+    header::TE,
+    header::TRAILER,
+    header::TRANSFER_ENCODING,
+    header::UPGRADE,
+];
+
+impl ForwardArgs {
+    pub async fn run(&self) -> Result<()> {
+        let client = ApiClient::new();
+        let deployment = describe::fetch(&client, &self.project, &self.service).await?;
+        let target = match deployment.config {
+            DescribeDeploymentDeploymentConfig::KatanaConfig(config) => config.rpc,
+            // Forward the whole torii host so both /graphql and its other routes work.
+            DescribeDeploymentDeploymentConfig::ToriiConfig(config) => config
+                .graphql
+                .trim_end_matches('/')
+                .trim_end_matches("/graphql")
+                .to_string(),
+        };
+
+        // Loopback only: every forwarded request carries the user's credentials.
+        let listener = TcpListener::bind(("127.0.0.1", self.local_port))
+            .map_err(|e| anyhow!("Failed to listen on port {}: {e}", self.local_port))?;
+
+        let credentials = Credentials::load()?;
+        let proxy = Arc::new(Proxy {
+            client: reqwest::Client::new(),
+            target: target.clone(),
+            authorization: HeaderValue::from_str(&format!("Bearer {}", credentials.access_token))?,
+            port: listener.local_addr()?.port(),
+        });
+
+        let local = format!("http://{}", listener.local_addr()?);
+        if output::is_json() {
+            // One line, printed once the port is bound, so a script can read it and go on.
+            println!("{}", json!({ "local": local, "target": target }));
+        } else {
+            println!("Forwarding {local} -> {target}");
+            println!("Press Ctrl-C to stop.");
+        }
+
+        let router = Router::new().fallback(forward).with_state(proxy);
+        axum::Server::from_tcp(listener)?
+            .serve(router.into_make_service())
+            .with_graceful_shutdown(async {
+                let _ = tokio::signal::ctrl_c().await;
+            })
+            .await?;
+
+        Ok(())
+    }
+}
+
+async fn forward(
+    State(proxy): State<Arc<Proxy>>,
+    method: Method,
+    uri: Uri,
+    mut headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let url = match uri.path_and_query().map(|p| p.as_str()) {
+        None | Some("/") => proxy.target.clone(),
+        Some(path) => format!("{}{path}", proxy.target),
+    };
+
+    // Binding to loopback doesn't stop a web page in the browser, or a DNS rebinding attack,
+    // from reaching the proxy, so only requests addressed to it from local origins get through.
+    if !is_local_request(&headers, proxy.port) {
+        let message = "Only requests to this proxy from localhost are forwarded.";
+        return (StatusCode::FORBIDDEN, message).into_response();
+    }
+
+    strip_hop_by_hop(&mut headers);
+    for name in [header::HOST, header::ORIGIN, header::COOKIE] {
+        headers.remove(name);
+    }
+    headers.insert(header::AUTHORIZATION, proxy.authorization.clone());
+
+    let res = match proxy
+        .client
+        .request(method, &url)
+        .headers(headers)
+        .body(body)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(e) => {
+            let message = format!("Failed to reach {}: {e}", proxy.target);
+            return (StatusCode::BAD_GATEWAY, message).into_response();
+        }
+    };
+
+    let status = res.status();
+    let mut headers = res.headers().clone();
+    // The body is buffered, so the upstream framing no longer applies.
+    strip_hop_by_hop(&mut headers);
+    headers.remove(header::CONTENT_LENGTH);
+
+    match res.bytes().await {
+        Ok(body) => (status, headers, body).into_response(),
+        Err(e) => {
+            let message = format!("Failed to read the response from {}: {e}", proxy.target);
+            (StatusCode::BAD_GATEWAY, message).into_response()
+        }
+    }
+}
+
+/// `Host` must name this proxy on loopback, and an `Origin`, when there is one, must be a
+/// page served from this machine, such as a local dev server.
+fn is_local_request(headers: &HeaderMap, port: u16) -> bool {
+    let Some(host) = headers.get(header::HOST).and_then(|h| h.to_str().ok()) else {
+        return false;
+    };
+    let (name, host_port) = match host.rsplit_once(':') {
+        Some((name, p)) => (name, p.parse().ok()),
+        None => (host, Some(80)),
+    };
+    if !is_loopback(name) || host_port != Some(port) {
+        return false;
+    }
+
+    match headers.get(header::ORIGIN) {
+        None => true,
+        Some(origin) => origin
+            .to_str()
+            .ok()
+            .and_then(|origin| Uri::try_from(origin).ok())
+            .is_some_and(|origin| origin.host().is_some_and(is_loopback)),
+    }
+}
+
+fn is_loopback(host: &str) -> bool {
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+/// Drop the hop-by-hop headers, including any the `Connection` header lists.
+fn strip_hop_by_hop(headers: &mut HeaderMap) {
+    let listed: Vec<String> = headers
+        .get_all(header::CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|name| name.trim().to_ascii_lowercase())
+        .collect();
+    for name in listed.iter().map(String::as_str).chain(["keep-alive"]) {
+        headers.remove(name);
+    }
+    for name in HOP_BY_HOP {
+        headers.remove(name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(header::HeaderName, &str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.clone(), HeaderValue::from_str(value).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn only_requests_for_this_proxy_from_local_pages_are_allowed() {
+        let allowed =
+            |pairs: &[(header::HeaderName, &str)]| is_local_request(&headers(pairs), 5050);
+
+        assert!(allowed(&[(header::HOST, "localhost:5050")]));
+        assert!(allowed(&[(header::HOST, "127.0.0.1:5050")]));
+        assert!(allowed(&[
+            (header::HOST, "localhost:5050"),
+            (header::ORIGIN, "http://localhost:3000"),
+        ]));
+
+        assert!(!allowed(&[]));
+        assert!(!allowed(&[(header::HOST, "localhost:8080")]));
+        assert!(!allowed(&[(header::HOST, "attacker.example:5050")]));
+        assert!(!allowed(&[
+            (header::HOST, "localhost:5050"),
+            (header::ORIGIN, "https://attacker.example"),
+        ]));
+        assert!(!allowed(&[
+            (header::HOST, "localhost:5050"),
+            (header::ORIGIN, "null"),
+        ]));
+    }
+
+    #[test]
+    fn hop_by_hop_headers_are_stripped() {
+        let mut map = headers(&[
+            (header::CONNECTION, "keep-alive, x-session"),
+            (header::UPGRADE, "websocket"),
+            (header::CONTENT_TYPE, "application/json"),
+        ]);
+        map.insert("x-session", HeaderValue::from_static("1"));
+        map.insert("keep-alive", HeaderValue::from_static("timeout=5"));
+
+        strip_hop_by_hop(&mut map);
+
+        assert_eq!(map.len(), 1);
+        assert!(map.contains_key(header::CONTENT_TYPE));
+    }
+}
@@ -1,62 +1,250 @@
 #![allow(clippy::enum_variant_names)]
 
+use std::{fmt::Write, time::Duration};
+
 use anyhow::Result;
-use clap::Args;
-use graphql_client::{GraphQLQuery, Response};
+use clap::{Args, ValueEnum};
+use graphql_client::GraphQLQuery;
+
+use crate::{api::ApiClient, cache, config::Config, output, project::ProjectConfig};
 
-use crate::api::ApiClient;
+use super::{services::Service, watch::watch, Long};
 
-use self::list_deployments::{ResponseData, Variables};
+type BigInt = String;
+type ChainID = String;
+type Cursor = String;
+type Time = String;
+
+const PAGE_SIZE: i64 = 100;
 
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "schema.json",
     query_path = "src/command/deployments/list.graphql",
-    response_derives = "Debug"
+    response_derives = "Debug",
+    variables_derives = "Default"
 )]
 pub struct ListDeployments;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.json",
+    query_path = "src/command/deployments/list.graphql",
+    response_derives = "Debug",
+    variables_derives = "Default"
+)]
+pub struct TeamDeployments;
+
+#[derive(Clone, Debug, ValueEnum)]
+pub enum Sort {
+    Project,
+    Created,
+}
+
 #[derive(Debug, Args)]
 #[command(next_help_heading = "List options")]
-pub struct ListArgs {}
+pub struct ListArgs {
+    #[arg(long, value_name = "team")]
+    #[arg(
+        help = "Only list deployments of this team. Defaults to .slot/project.toml, then `slot config`."
+    )]
+    pub team: Option<String>,
+
+    #[arg(long, value_name = "service")]
+    #[arg(help = "Only list deployments of this service.")]
+    pub service: Option<Service>,
+
+    #[arg(long, value_name = "field")]
+    #[arg(help = "Sort deployments by this field.")]
+    pub sort: Option<Sort>,
+
+    #[arg(short, long)]
+    #[arg(help = "Keep refreshing the list, highlighting what changed.")]
+    pub watch: bool,
+
+    #[arg(long, default_value = "2", requires = "watch")]
+    #[arg(value_name = "seconds")]
+    #[arg(help = "How often `--watch` refreshes.")]
+    pub interval: u64,
+
+    #[arg(long, default_value = "0", value_name = "seconds")]
+    #[arg(help = "Reuse a cached response up to this old instead of querying the API.")]
+    pub max_age: u64,
+}
+
+/// A deployment flattened out of its team's connection.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Entry {
+    pub team: String,
+    pub project: String,
+    pub service: String,
+    pub created_at: String,
+}
 
 impl ListArgs {
     pub async fn run(&self) -> Result<()> {
-        let request_body = ListDeployments::build_query(Variables {});
-
         let client = ApiClient::new();
-        let res: Response<ResponseData> = client.post(&request_body).await?;
-        if let Some(errors) = res.errors.clone() {
-            for err in errors {
-                println!("Error: {}", err.message);
-            }
-        }
-
-        if let Some(data) = res.data {
-            if let Some(me) = data.me {
-                if let Some(teams) = me.teams.edges {
-                    let teams: Vec<_> = teams
-                        .iter()
-                        .filter_map(|team| team.as_ref())
-                        .filter_map(|team| team.node.as_ref())
-                        .collect::<_>();
-
-                    let deployments: Vec<_> = teams
-                        .iter()
-                        .filter_map(|team| team.deployments.edges.as_ref())
-                        .flatten()
-                        .map(|deployment| deployment.as_ref().unwrap())
-                        .collect();
-
-                    for deployment in deployments {
-                        println!("Project: {}", deployment.node.as_ref().unwrap().project);
-                        println!("Service: {}", deployment.node.as_ref().unwrap().service.id);
-                        println!("---");
-                    }
-                }
-            }
+        if self.watch {
+            let interval = Duration::from_secs(self.interval);
+            return watch(interval, || self.render(&client)).await;
         }
 
+        print!("{}", self.render(&client).await?);
         Ok(())
     }
+
+    async fn render(&self, client: &ApiClient) -> Result<String> {
+        let team = team(&self.team)?;
+        let key = format!("list-{team:?}-{:?}", self.service);
+        let mut entries = cache::fetch_or_cached(
+            &key,
+            Duration::from_secs(self.max_age),
+            fetch(client, team.as_deref(), self.service.as_ref()),
+        )
+        .await?;
+
+        match self.sort {
+            Some(Sort::Project) => entries.sort_by(|a, b| a.project.cmp(&b.project)),
+            Some(Sort::Created) => entries.sort_by(|a, b| a.created_at.cmp(&b.created_at)),
+            None => {}
+        }
+
+        if output::is_json() {
+            return Ok(serde_json::to_string_pretty(&entries)? + "\n");
+        }
+
+        let mut out = String::new();
+        for entry in entries {
+            writeln!(out, "Project: {}", entry.project)?;
+            writeln!(out, "Service: {}", entry.service)?;
+            writeln!(out, "Team: {}", entry.team)?;
+            writeln!(out, "---")?;
+        }
+
+        Ok(out)
+    }
+}
+
+/// `--team`, else the team in .slot/project.toml, else `team` in the user config.
+pub(crate) fn team(flag: &Option<String>) -> Result<Option<String>> {
+    if flag.is_some() {
+        return Ok(flag.clone());
+    }
+    let team = ProjectConfig::load()?.team;
+    Ok(team.or_else(|| Config::get().team.clone()))
+}
+
+/// Fetch every deployment the user can see, optionally narrowed to one team or service.
+pub(crate) async fn fetch(
+    client: &ApiClient,
+    team: Option<&str>,
+    service: Option<&Service>,
+) -> Result<Vec<Entry>> {
+    let service_id = service.map(|service| format!("{service:?}").to_lowercase());
+
+    let data = client
+        .query::<ListDeployments>(list_deployments::Variables {
+            first: Some(PAGE_SIZE),
+            where_: Some(list_deployments::DeploymentWhereInput {
+                service_id: service_id.clone(),
+                ..Default::default()
+            }),
+        })
+        .await?;
+
+    let teams = data
+        .me
+        .and_then(|me| me.teams.edges)
+        .unwrap_or_default()
+        .into_iter()
+        .flatten()
+        .filter_map(|edge| edge.node)
+        .filter(|node| !matches!(team, Some(name) if node.name != name));
+
+    let mut entries = Vec::new();
+    for team in teams {
+        for deployment in team
+            .deployments
+            .edges
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|edge| edge.node)
+        {
+            entries.push(Entry {
+                team: team.name.clone(),
+                project: deployment.project,
+                service: deployment.service.id,
+                created_at: deployment.created_at,
+            });
+        }
+
+        let page = team.deployments.page_info;
+        if page.has_next_page {
+            fetch_remaining(
+                client,
+                &team.id,
+                &team.name,
+                page.end_cursor,
+                &service_id,
+                &mut entries,
+            )
+            .await?;
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Follow the team's deployment cursor until every page has been read.
+async fn fetch_remaining(
+    client: &ApiClient,
+    team_id: &str,
+    team_name: &str,
+    mut after: Option<Cursor>,
+    service_id: &Option<String>,
+    entries: &mut Vec<Entry>,
+) -> Result<()> {
+    use team_deployments::TeamDeploymentsNode;
+
+    while after.is_some() {
+        let data = client
+            .query::<TeamDeployments>(team_deployments::Variables {
+                team: team_id.to_string(),
+                first: Some(PAGE_SIZE),
+                after: after.take(),
+                where_: Some(team_deployments::DeploymentWhereInput {
+                    service_id: service_id.clone(),
+                    ..Default::default()
+                }),
+            })
+            .await?;
+
+        let Some(TeamDeploymentsNode::Team(team)) = data.node else {
+            break;
+        };
+
+        for deployment in team
+            .deployments
+            .edges
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|edge| edge.node)
+        {
+            entries.push(Entry {
+                team: team_name.to_string(),
+                project: deployment.project,
+                service: deployment.service.id,
+                created_at: deployment.created_at,
+            });
+        }
+
+        let page = team.deployments.page_info;
+        if page.has_next_page {
+            after = page.end_cursor;
+        }
+    }
+
+    Ok(())
 }
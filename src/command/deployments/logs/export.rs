@@ -0,0 +1,408 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, IsTerminal, Write},
+    path::PathBuf,
+};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+use clap::Args;
+use flate2::{write::GzEncoder, Compression};
+use serde_json::{json, Value};
+
+use crate::{output, project};
+
+use super::{overlap, strip_ansi, Level, LogReader, Service};
+
+/// Lines requested per page while walking the history.
+const PAGE_SIZE: i64 = 1000;
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Export options")]
+pub struct ExportArgs {
+    #[arg(help = "The project of the deployment. Defaults to the one in .slot/project.toml.")]
+    pub project: Option<String>,
+
+    #[arg(help = "The name of the deployment service. Defaults to the one in .slot/project.toml.")]
+    pub service: Option<Service>,
+
+    #[arg(long, value_name = "time", value_parser = parse_time)]
+    #[arg(help = "Export logs from this time, e.g. 2024-06-01T00:00Z.")]
+    pub from: DateTime<Utc>,
+
+    #[arg(long, value_name = "time", value_parser = parse_time)]
+    #[arg(help = "Export logs up to this time. Defaults to now.")]
+    pub to: Option<DateTime<Utc>>,
+
+    #[arg(long, value_name = "path")]
+    #[arg(help = "Where to write the logs, one JSON object per line. Gzipped if it ends in .gz.")]
+    pub file: PathBuf,
+
+    #[arg(long)]
+    #[arg(help = "Overwrite the file if it already exists.")]
+    pub force: bool,
+}
+
+impl ExportArgs {
+    pub async fn run(&self) -> Result<()> {
+        let (project, service) = project::deployment(&self.project, &self.service)?;
+        let to = self.to.unwrap_or_else(Utc::now);
+        if self.from >= to {
+            return Err(anyhow!("`--from` must be before `--to`."));
+        }
+        if self.file.exists() && !self.force {
+            return Err(anyhow!(
+                "{} already exists. Pass `--force` to overwrite it.",
+                self.file.display()
+            ));
+        }
+
+        let reader = LogReader::new(service, project);
+        let mut file = BufWriter::new(File::create(&self.file)?);
+        let written = if self.file.extension().is_some_and(|ext| ext == "gz") {
+            let mut gz = GzEncoder::new(&mut file, Compression::default());
+            self.export(&reader, to, &mut gz)
+                .await
+                .and_then(|lines| Ok(gz.finish().map(|_| lines)?))
+        } else {
+            self.export(&reader, to, &mut file).await
+        }
+        .and_then(|lines| Ok(file.flush().map(|_| lines)?));
+
+        if io::stderr().is_terminal() {
+            eprint!("\r\x1b[K");
+        }
+        match written {
+            Ok(lines) => {
+                if output::is_json() {
+                    output::print_json(&json!({ "path": self.file, "lines": lines }))?;
+                } else {
+                    println!("Wrote {lines} lines to {}", self.file.display());
+                }
+                Ok(())
+            }
+            Err(e) => {
+                // A partial export reads like a complete one, so don't leave it behind.
+                drop(file);
+                let _ = fs::remove_file(&self.file);
+                Err(e)
+            }
+        }
+    }
+
+    /// Walk the history from `--from` a page at a time, returning how many lines were written.
+    async fn export(
+        &self,
+        reader: &LogReader,
+        to: DateTime<Utc>,
+        out: &mut impl Write,
+    ) -> Result<usize> {
+        // The cursor goes back to the API exactly as it came, since rounding it would
+        // refetch or skip whatever was logged in the rest of that second.
+        let mut since = format_time(self.from);
+        let mut since_time = self.from;
+        // The previous page. The cursor is inclusive, so the next one can start with its tail.
+        let mut previous: Vec<String> = Vec::new();
+        // When the line being read was logged. Lines without a timestamp, like the frames of
+        // a stack trace, belong to the timestamped line they follow.
+        let mut logged_at: Option<DateTime<Utc>> = None;
+        let mut lines = 0;
+        loop {
+            let logs = reader.page(since.clone(), PAGE_SIZE).await?;
+            let page: Vec<&str> = logs
+                .content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .collect();
+            let full = page.len() as i64 >= PAGE_SIZE;
+            if since_time == self.from && full {
+                ensure_oldest_first(reader, &since, page[0]).await?;
+            }
+
+            let new = &page[overlap(&previous, &page)..];
+            for line in new {
+                let object = to_object(&strip_ansi(line));
+                if let Some(time) = timestamp(&object) {
+                    logged_at = Some(time);
+                }
+                // Pages start at `--from` but can run past `--to`; drop what's outside.
+                if !logged_at.is_some_and(|time| time >= self.from && time <= to) {
+                    continue;
+                }
+                writeln!(out, "{object}")?;
+                lines += 1;
+            }
+            let empty = new.is_empty();
+            previous = page.into_iter().map(String::from).collect();
+
+            let until = DateTime::parse_from_rfc3339(&logs.until)
+                .map_err(|e| anyhow!("Unexpected log cursor {}: {e}", logs.until))?
+                .with_timezone(&Utc);
+            // A full page that doesn't move the cursor is one timestamp with more lines than
+            // a page holds; the rest of them can't be reached.
+            if full && until <= since_time {
+                return Err(anyhow!(
+                    "More than {PAGE_SIZE} lines were logged at {}, so the export can't page \
+                     past them and would be incomplete.",
+                    logs.until
+                ));
+            }
+            // An empty page, or one that doesn't move the cursor, is the end of the history.
+            if empty || until <= since_time || until >= to {
+                return Ok(lines);
+            }
+            since = logs.until;
+            since_time = until;
+            if io::stderr().is_terminal() {
+                eprint!(
+                    "\r\x1b[KExported {lines} lines up to {}",
+                    format_time(since_time)
+                );
+            }
+        }
+    }
+}
+
+/// A full first page is only the start of the range if the API honoured the order; one that
+/// ignores it hands back the most recent lines, and paging on from there would silently
+/// skip the rest. The single oldest line tells the two apart.
+async fn ensure_oldest_first(reader: &LogReader, since: &str, first: &str) -> Result<()> {
+    let oldest = reader.page(since.to_string(), 1).await?;
+    if oldest.content.lines().find(|line| !line.trim().is_empty()) == Some(first) {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "The API returned the latest {PAGE_SIZE} lines rather than the oldest, so this export \
+         would be missing the start of the range. Try a later `--from`."
+    ))
+}
+
+/// Structured lines are kept as they are; plain ones become `{"level", "message"}`.
+fn to_object(plain: &str) -> Value {
+    match serde_json::from_str::<Value>(plain) {
+        Ok(object) if object.is_object() => object,
+        _ => {
+            let level = plain.split_whitespace().take(3).find_map(Level::parse);
+            json!({ "level": level.map(|l| l.as_str()), "message": plain })
+        }
+    }
+}
+
+/// The time a line was logged, from its `timestamp` field or leading RFC3339 timestamp.
+fn timestamp(object: &Value) -> Option<DateTime<Utc>> {
+    let time = match object["timestamp"].as_str() {
+        Some(time) => time,
+        None => object["message"].as_str()?.split_whitespace().next()?,
+    };
+    DateTime::parse_from_rfc3339(time)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// RFC3339, RFC3339 without seconds (2024-06-01T00:00Z), or a date (2024-06-01) in UTC.
+fn parse_time(s: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    if let Ok(time) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%MZ") {
+        return Ok(time.and_utc());
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    }
+    Err(format!("`{s}` is not an RFC3339 time or a date"))
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use chrono::Duration;
+
+    use super::*;
+    use crate::api::{ApiClient, ApiError, ApiRequest, SlotApi};
+
+    /// Lines `interval` apart from midnight, served the way the logs API pages them: from an
+    /// inclusive `since`, oldest first if asked to be and `honours_order`, else the latest.
+    struct FakeLogs {
+        lines: Vec<(DateTime<Utc>, String)>,
+        honours_order: bool,
+    }
+
+    impl FakeLogs {
+        fn new(count: usize, interval: Duration, honours_order: bool) -> Self {
+            let start = midnight();
+            let lines = (0..count)
+                .map(|i| {
+                    let time = start + interval * i as i32;
+                    let stamp = time.to_rfc3339_opts(SecondsFormat::Millis, true);
+                    (time, format!("{stamp} INFO line {i}"))
+                })
+                .collect();
+            FakeLogs {
+                lines,
+                honours_order,
+            }
+        }
+
+        /// Follow every line with an untimestamped one, like a stack trace frame.
+        fn with_traces(self) -> Self {
+            let lines = self
+                .lines
+                .into_iter()
+                .enumerate()
+                .flat_map(|(i, (time, line))| [(time, line), (time, format!("  at frame {i}"))])
+                .collect();
+            FakeLogs { lines, ..self }
+        }
+    }
+
+    #[async_trait]
+    impl SlotApi for FakeLogs {
+        async fn execute(&self, request: ApiRequest) -> Result<Value, ApiError> {
+            let variables = &request.body["variables"];
+            let since = parse_time(variables["since"].as_str().unwrap()).unwrap();
+            let limit = variables["limit"].as_u64().unwrap() as usize;
+            let after: Vec<_> = self.lines.iter().filter(|(t, _)| *t >= since).collect();
+            let page = if self.honours_order && variables["order"] == "asc" {
+                &after[..limit.min(after.len())]
+            } else {
+                &after[after.len().saturating_sub(limit)..]
+            };
+
+            let content: Vec<&str> = page.iter().map(|(_, line)| line.as_str()).collect();
+            let until = page.last().map_or(since, |(time, _)| *time);
+            Ok(json!({ "data": { "deployment": { "logs": {
+                "content": content.join("\n"),
+                "until": until.to_rfc3339_opts(SecondsFormat::Nanos, true),
+            } } } }))
+        }
+    }
+
+    fn midnight() -> DateTime<Utc> {
+        parse_time("2024-06-01").unwrap()
+    }
+
+    async fn export(api: FakeLogs) -> Result<Vec<String>> {
+        export_from(api, midnight()).await
+    }
+
+    async fn export_from(api: FakeLogs, from: DateTime<Utc>) -> Result<Vec<String>> {
+        let args = ExportArgs {
+            project: None,
+            service: None,
+            from,
+            to: None,
+            file: PathBuf::from("logs.jsonl"),
+            force: false,
+        };
+        let reader = LogReader::with_client(
+            ApiClient::with_api(api),
+            Service::Katana,
+            "my-project".to_string(),
+        );
+
+        let mut out = Vec::new();
+        let written = args
+            .export(&reader, midnight() + Duration::hours(1), &mut out)
+            .await?;
+        let lines: Vec<String> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap()["message"].to_string())
+            .collect();
+        assert_eq!(lines.len(), written);
+        Ok(lines)
+    }
+
+    fn numbered(lines: &[String]) -> Vec<usize> {
+        lines
+            .iter()
+            .map(|line| {
+                line.trim_matches('"')
+                    .rsplit(' ')
+                    .next()
+                    .unwrap()
+                    .parse()
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn pages_cover_the_range_once_across_sub_second_boundaries() {
+        // 400 lines a second, so every page ends partway through a second.
+        let lines = export(FakeLogs::new(2500, Duration::microseconds(2500), true))
+            .await
+            .unwrap();
+
+        assert_eq!(numbered(&lines), (0..2500).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn ranges_that_fit_one_page_export_when_the_api_returns_the_latest_lines() {
+        let lines = export(FakeLogs::new(500, Duration::seconds(1), false))
+            .await
+            .unwrap();
+
+        assert_eq!(numbered(&lines), (0..500).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn longer_ranges_fail_when_the_api_returns_the_latest_lines() {
+        let error = export(FakeLogs::new(2500, Duration::seconds(1), false))
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("rather than the oldest"));
+    }
+
+    /// Each line followed by its frame, as they come out of the export.
+    fn traced(lines: std::ops::Range<usize>) -> Vec<String> {
+        let stamp = |i: usize| {
+            (midnight() + Duration::seconds(i as i64)).to_rfc3339_opts(SecondsFormat::Millis, true)
+        };
+        lines
+            .flat_map(|i| {
+                [
+                    format!("{} INFO line {i}", stamp(i)),
+                    format!("  at frame {i}"),
+                ]
+            })
+            .map(|message| Value::String(message).to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn untimestamped_lines_follow_their_line_once_across_pages() {
+        // 1500 lines and 1500 frames, so pages end between a line and its frame too.
+        let api = FakeLogs::new(1500, Duration::seconds(1), true).with_traces();
+
+        let lines = export(api).await.unwrap();
+
+        assert_eq!(lines, traced(0..1500));
+    }
+
+    #[tokio::test]
+    async fn untimestamped_lines_before_the_range_are_dropped() {
+        let api = FakeLogs::new(10, Duration::seconds(1), true).with_traces();
+
+        let lines = export_from(api, midnight() + Duration::seconds(3))
+            .await
+            .unwrap();
+
+        assert_eq!(lines, traced(3..10));
+    }
+
+    #[tokio::test]
+    async fn more_lines_at_one_timestamp_than_a_page_holds_fail() {
+        let error = export(FakeLogs::new(1500, Duration::zero(), true))
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("can't page past them"));
+    }
+}
@@ -0,0 +1,592 @@
+use std::{
+    io::{self, IsTerminal},
+    sync::OnceLock,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand, ValueEnum};
+use graphql_client::GraphQLQuery;
+use regex::Regex;
+use serde_json::{json, Value};
+use tokio::{task::JoinSet, time::sleep};
+
+use crate::{
+    api::ApiClient,
+    command::deployments::logs::deployment_logs::DeploymentService,
+    output,
+    project::{self, ProjectConfig},
+};
+
+use self::deployment_logs::{DeploymentLogsDeploymentLogs, Order, Variables};
+
+use super::services::Service;
+
+pub use self::export::ExportArgs;
+
+mod export;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.json",
+    query_path = "src/command/deployments/logs/logs.graphql",
+    response_derives = "Debug"
+)]
+pub struct DeploymentLogs;
+
+type Time = String;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+/// Lines read per request while following; a busy poll reads several pages.
+const POLL_PAGE: i64 = 500;
+/// Label colors for multiplexed logs, in the order docker compose uses them.
+const LABEL_COLORS: [u8; 6] = [36, 33, 32, 35, 34, 31];
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Deployment logs options")]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct LogsArgs {
+    #[command(subcommand)]
+    pub command: Option<LogsCommand>,
+
+    #[arg(help = "The project of the deployment. Defaults to the one in .slot/project.toml.")]
+    pub project: Option<String>,
+
+    #[arg(help = "The name of the deployment service. Defaults to the one in .slot/project.toml.")]
+    pub service: Option<Service>,
+
+    #[arg(short, long = "since")]
+    #[arg(help = "Display logs after this RFC3339 timestamp.")]
+    pub since: Option<String>,
+
+    #[arg(short, long = "limit", visible_alias = "tail", default_value = "25")]
+    #[arg(help = "Display only the most recent `n` lines of logs.")]
+    pub limit: i64,
+
+    #[arg(short, long = "follow", default_value = "false")]
+    #[arg(help = "Stream service logs.")]
+    pub follow: bool,
+
+    #[arg(long, value_name = "regex")]
+    #[arg(help = "Only display lines matching this regular expression.")]
+    pub grep: Option<Regex>,
+
+    #[arg(long, value_name = "level")]
+    #[arg(help = "Only display lines at this level or above.")]
+    pub level: Option<Level>,
+
+    #[arg(long)]
+    #[arg(help = "Print one JSON object per line.")]
+    pub json: bool,
+
+    #[arg(long = "project", value_name = "project")]
+    #[arg(help = "Also show the logs of this project. Can be repeated.")]
+    pub projects: Vec<String>,
+
+    #[arg(long, conflicts_with = "service")]
+    #[arg(help = "Show the logs of both katana and torii.")]
+    pub all_services: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LogsCommand {
+    #[command(about = "Write a time range of logs to a file, for postmortems.")]
+    Export(ExportArgs),
+}
+
+impl LogsArgs {
+    pub async fn run(&self) -> Result<()> {
+        if let Some(LogsCommand::Export(args)) = &self.command {
+            return args.run().await;
+        }
+
+        if !self.projects.is_empty() || self.all_services {
+            return self.run_many().await;
+        }
+
+        let (project, service) = project::deployment(&self.project, &self.service)?;
+        let reader = LogReader::new(service, project);
+        let printer = LogPrinter {
+            grep: self.grep.clone(),
+            level: self.level,
+            json: self.json || output::is_json(),
+            ..Default::default()
+        };
+
+        if self.follow {
+            reader
+                .stream(self.since.clone(), self.limit, &printer)
+                .await?;
+        } else {
+            let logs = reader.query(self.since.clone(), self.limit).await?;
+            printer.print(&logs.content);
+        }
+
+        Ok(())
+    }
+
+    /// Show the logs of several deployments at once, each line labeled with where it came from.
+    async fn run_many(&self) -> Result<()> {
+        let mut projects: Vec<String> =
+            self.project.iter().chain(&self.projects).cloned().collect();
+        if projects.is_empty() {
+            projects.extend(ProjectConfig::load()?.project);
+        }
+        let services = if self.all_services {
+            vec![Service::Katana, Service::Torii]
+        } else {
+            let project = projects.first().cloned();
+            vec![project::deployment(&project, &self.service)?.1]
+        };
+
+        let targets: Vec<(String, Service)> = projects
+            .iter()
+            .flat_map(|project| services.iter().map(move |s| (project.clone(), s.clone())))
+            .collect();
+        if targets.is_empty() {
+            return Err(anyhow!("Pass at least one project with `--project`."));
+        }
+
+        let labels: Vec<String> = targets
+            .iter()
+            .map(|(project, service)| {
+                format!("{project}/{}", format!("{service:?}").to_lowercase())
+            })
+            .collect();
+        let width = labels.iter().map(String::len).max().unwrap_or(0);
+        let colored = io::stdout().is_terminal();
+
+        let mut tasks = JoinSet::new();
+        for (i, ((project, service), label)) in targets.into_iter().zip(labels).enumerate() {
+            let prefix = if colored {
+                let color = LABEL_COLORS[i % LABEL_COLORS.len()];
+                format!("\x1b[{color}m{label:<width$} |\x1b[0m ")
+            } else {
+                format!("{label:<width$} | ")
+            };
+            let printer = LogPrinter {
+                grep: self.grep.clone(),
+                level: self.level,
+                json: self.json || output::is_json(),
+                source: Some((label.clone(), prefix)),
+            };
+            let reader = LogReader::new(service, project);
+            let (since, limit, follow) = (self.since.clone(), self.limit, self.follow);
+
+            tasks.spawn(async move {
+                let result = if follow {
+                    reader.stream(since, limit, &printer).await
+                } else {
+                    reader
+                        .query(since, limit)
+                        .await
+                        .map(|logs| printer.print(&logs.content))
+                };
+                (label, result)
+            });
+        }
+
+        // One missing deployment, e.g. a project without torii, shouldn't stop the others.
+        while let Some(task) = tasks.join_next().await {
+            if let (label, Err(e)) = task? {
+                eprintln!("Error: {label}: {e}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_uppercase().as_str() {
+            "TRACE" => Some(Level::Trace),
+            "DEBUG" => Some(Level::Debug),
+            "INFO" => Some(Level::Info),
+            "WARN" | "WARNING" => Some(Level::Warn),
+            "ERROR" => Some(Level::Error),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Level::Trace => "trace",
+            Level::Debug => "debug",
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+        }
+    }
+}
+
+/// Filters and formats log lines before printing.
+#[derive(Debug, Default)]
+pub struct LogPrinter {
+    pub grep: Option<Regex>,
+    pub level: Option<Level>,
+    pub json: bool,
+    /// The deployment's label and the prefix printed before its lines, when showing several.
+    pub source: Option<(String, String)>,
+}
+
+impl LogPrinter {
+    pub fn print(&self, content: &str) {
+        for line in self.format(content) {
+            println!("{line}");
+        }
+    }
+
+    /// The lines of `content` that pass the filters, as they should be printed.
+    fn format(&self, content: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            let plain = strip_ansi(line);
+            let structured = serde_json::from_str::<Value>(&plain)
+                .ok()
+                .filter(Value::is_object);
+            let level = match &structured {
+                Some(object) => object["level"].as_str().and_then(Level::parse),
+                None => plain.split_whitespace().take(3).find_map(Level::parse),
+            };
+
+            if self
+                .grep
+                .as_ref()
+                .is_some_and(|grep| !grep.is_match(&plain))
+            {
+                continue;
+            }
+            if self
+                .level
+                .is_some_and(|min| !matches!(level, Some(level) if level >= min))
+            {
+                continue;
+            }
+
+            if !self.json {
+                let prefix = self.source.as_ref().map_or("", |(_, prefix)| prefix);
+                lines.push(format!("{prefix}{line}"));
+                continue;
+            }
+
+            let mut object = structured
+                .unwrap_or_else(|| json!({ "level": level.map(|l| l.as_str()), "message": plain }));
+            if let Some((label, _)) = &self.source {
+                object["source"] = json!(label);
+            }
+            lines.push(object.to_string());
+        }
+        lines
+    }
+}
+
+/// Cleared on Ctrl-C. The handler can only be installed once, and several streams may run.
+fn running() -> Arc<AtomicBool> {
+    static RUNNING: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+    RUNNING
+        .get_or_init(|| {
+            let running = Arc::new(AtomicBool::new(true));
+            let r = running.clone();
+            ctrlc::set_handler(move || {
+                r.store(false, Ordering::SeqCst);
+            })
+            .expect("Error setting Ctrl-C handler");
+            running
+        })
+        .clone()
+}
+
+/// Services log with colors; drop the escape codes so filters see plain text.
+pub(crate) fn strip_ansi(line: &str) -> String {
+    static ANSI: OnceLock<Regex> = OnceLock::new();
+    let ansi = ANSI.get_or_init(|| Regex::new(r"\x1b\[[0-9;]*[A-Za-z]").unwrap());
+    ansi.replace_all(line, "").into_owned()
+}
+
+/// The cursor is inclusive, so a page can start with the lines that ended the one before.
+/// How many of them it repeats.
+pub(crate) fn overlap(previous: &[String], page: &[&str]) -> usize {
+    (1..=page.len().min(previous.len()))
+        .rev()
+        .find(|&n| {
+            previous[previous.len() - n..]
+                .iter()
+                .zip(&page[..n])
+                .all(|(a, b)| a == b)
+        })
+        .unwrap_or(0)
+}
+
+fn non_empty_lines(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(String::from)
+        .collect()
+}
+
+pub struct LogReader {
+    client: ApiClient,
+    service: Service,
+    project: String,
+}
+
+impl LogReader {
+    pub fn new(service: Service, project: String) -> Self {
+        Self::with_client(ApiClient::new(), service, project)
+    }
+
+    pub fn with_client(client: ApiClient, service: Service, project: String) -> Self {
+        LogReader {
+            client,
+            service,
+            project,
+        }
+    }
+
+    /// Up to `limit` lines, in the server's default order: the most recent ones.
+    pub async fn query(
+        &self,
+        since: Option<String>,
+        limit: i64,
+    ) -> Result<DeploymentLogsDeploymentLogs> {
+        self.fetch(since, limit, None).await
+    }
+
+    /// Up to `limit` of the oldest lines from `since` on, for walking the history forward.
+    pub async fn page(&self, since: String, limit: i64) -> Result<DeploymentLogsDeploymentLogs> {
+        self.fetch(Some(since), limit, Some(Order::asc)).await
+    }
+
+    async fn fetch(
+        &self,
+        since: Option<String>,
+        limit: i64,
+        order: Option<Order>,
+    ) -> Result<DeploymentLogsDeploymentLogs> {
+        let service = match self.service {
+            Service::Katana => DeploymentService::katana,
+            Service::Torii => DeploymentService::torii,
+        };
+
+        let data = self
+            .client
+            .query::<DeploymentLogs>(Variables {
+                project: self.project.clone(),
+                service,
+                since,
+                limit: Some(limit),
+                order,
+            })
+            .await?;
+
+        data.deployment
+            .map(|deployment| deployment.logs)
+            .ok_or_else(|| anyhow::anyhow!("Deployment {} not found.", self.project))
+    }
+
+    /// Print the last `tail` lines, then poll for new ones until Ctrl-C. Failed polls are
+    /// retried with exponential backoff so a flaky connection doesn't end the stream.
+    pub async fn stream(
+        &self,
+        since: Option<String>,
+        tail: i64,
+        printer: &LogPrinter,
+    ) -> Result<()> {
+        let running = running();
+
+        let logs = self.query(since, tail).await?;
+        printer.print(&logs.content);
+
+        let mut previous = non_empty_lines(&logs.content);
+        let mut since = logs.until;
+        let mut delay = POLL_INTERVAL;
+        while running.load(Ordering::SeqCst) {
+            sleep(delay).await;
+
+            match self.follow(&mut since, &mut previous).await {
+                Ok(lines) => {
+                    delay = POLL_INTERVAL;
+                    if !lines.is_empty() {
+                        printer.print(&lines.join("\n"));
+                    }
+                }
+                Err(e) => {
+                    delay = (delay * 2).min(MAX_RETRY_DELAY);
+                    eprintln!("Lost log stream ({e}), retrying in {}s...", delay.as_secs());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The lines logged from `since` on, read a page at a time until the cursor stops
+    /// moving. `previous` is the last page read, whose tail the next one repeats. Both are
+    /// only advanced once every page is in, so a failed poll is read again in full.
+    async fn follow(&self, since: &mut String, previous: &mut Vec<String>) -> Result<Vec<String>> {
+        let mut cursor = since.clone();
+        let mut last = previous.clone();
+        let mut lines = Vec::new();
+        loop {
+            let logs = self.page(cursor.clone(), POLL_PAGE).await?;
+            let page = non_empty_lines(&logs.content);
+            let borrowed: Vec<&str> = page.iter().map(String::as_str).collect();
+            lines.extend_from_slice(&page[overlap(&last, &borrowed)..]);
+
+            let full = page.len() as i64 >= POLL_PAGE;
+            let advanced = logs.until != cursor;
+            if !page.is_empty() {
+                last = page;
+            }
+            cursor = logs.until;
+            if !full || !advanced {
+                break;
+            }
+        }
+
+        *since = cursor;
+        *previous = last;
+        Ok(lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::MockApi;
+
+    fn reader(api: &MockApi, content: &str, until: &str) -> LogReader {
+        api.respond(
+            "DeploymentLogs",
+            json!({ "deployment": { "logs": { "content": content, "until": until } } }),
+        );
+        LogReader::with_client(
+            ApiClient::with_api(api.clone()),
+            Service::Katana,
+            "p".into(),
+        )
+    }
+
+    #[test]
+    fn pages_overlap_by_the_lines_repeated_at_the_cursor() {
+        let previous: Vec<String> = ["a", "b", "c"].map(String::from).to_vec();
+
+        assert_eq!(overlap(&previous, &["b", "c", "d"]), 2);
+        assert_eq!(overlap(&previous, &["c", "d"]), 1);
+        assert_eq!(overlap(&previous, &["d", "e"]), 0);
+        assert_eq!(overlap(&[], &["a"]), 0);
+    }
+
+    #[tokio::test]
+    async fn follow_prints_only_what_the_last_poll_did_not() {
+        let api = MockApi::new();
+        let reader = reader(&api, "b\nc\n", "t1");
+        let mut since = "t0".to_string();
+        let mut previous = vec!["a".to_string(), "b".to_string()];
+
+        let lines = reader.follow(&mut since, &mut previous).await.unwrap();
+
+        assert_eq!(lines, ["c"]);
+        assert_eq!(since, "t1");
+        assert_eq!(previous, ["b", "c"]);
+        assert_eq!(api.requests()[0].body["variables"]["since"], "t0");
+        assert_eq!(api.requests()[0].body["variables"]["order"], "asc");
+    }
+
+    #[tokio::test]
+    async fn follow_stops_paging_when_the_cursor_stops_moving() {
+        let api = MockApi::new();
+        let full: Vec<String> = (0..POLL_PAGE).map(|n| format!("line {n}")).collect();
+        let reader = reader(&api, &full.join("\n"), "t0");
+        let mut since = "t0".to_string();
+        let mut previous = Vec::new();
+
+        let lines = reader.follow(&mut since, &mut previous).await.unwrap();
+
+        assert_eq!(lines.len() as i64, POLL_PAGE);
+        assert_eq!(api.requests().len(), 1);
+        assert!(reader
+            .follow(&mut since, &mut previous)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    const LOGS: &str = "2024-01-01T00:00:00Z  INFO katana: started\n\
+                        2024-01-01T00:00:01Z \x1b[33m WARN\x1b[0m katana: slow block\n\
+                        {\"level\":\"ERROR\",\"message\":\"torii: lost connection\"}\n\
+                        \n\
+                        unleveled katana line\n";
+
+    #[test]
+    fn grep_matches_the_text_without_colors() {
+        let printer = LogPrinter {
+            grep: Some(Regex::new(r"WARN katana").unwrap()),
+            ..Default::default()
+        };
+
+        let lines = printer.format(LOGS);
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("slow block"));
+    }
+
+    #[test]
+    fn level_keeps_lines_at_or_above_it_and_drops_unleveled_ones() {
+        let printer = LogPrinter {
+            level: Some(Level::Warn),
+            ..Default::default()
+        };
+
+        let lines = printer.format(LOGS);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("slow block"));
+        assert!(lines[1].contains("lost connection"));
+    }
+
+    #[test]
+    fn filters_combine_and_json_output_carries_the_level_and_source() {
+        let printer = LogPrinter {
+            grep: Some(Regex::new("katana").unwrap()),
+            level: Some(Level::Info),
+            json: true,
+            source: Some(("p/katana".to_string(), "p/katana | ".to_string())),
+        };
+
+        let lines: Vec<Value> = printer
+            .format(LOGS)
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["level"], "info");
+        assert_eq!(lines[0]["source"], "p/katana");
+        assert_eq!(lines[1]["level"], "warn");
+    }
+
+    #[test]
+    fn text_lines_keep_their_source_prefix() {
+        let printer = LogPrinter {
+            source: Some(("p/katana".to_string(), "p/katana | ".to_string())),
+            ..Default::default()
+        };
+
+        let lines = printer.format("started\n");
+        assert_eq!(lines, ["p/katana | started"]);
+    }
+}
@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::{api::ApiClient, output, prompt};
+
+use super::{plan, Action, Manifest, Step};
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Apply options")]
+pub struct ApplyArgs {
+    #[arg(short, long, default_value = "slot.toml")]
+    #[arg(value_name = "path")]
+    #[arg(help = "The manifest describing the desired deployments.")]
+    pub file: PathBuf,
+
+    #[arg(short, long)]
+    #[arg(help = "Apply the changes without asking for confirmation.")]
+    pub yes: bool,
+}
+
+impl ApplyArgs {
+    pub async fn run(&self) -> Result<()> {
+        output::require_confirmed(self.yes, "--yes")?;
+        let manifest = Manifest::load(&self.file)?;
+        let client = ApiClient::new();
+        let steps = plan(&client, &manifest).await?;
+
+        if output::is_json() {
+            return apply_json(&client, steps).await;
+        }
+
+        if !steps.iter().any(|step| step.is_change()) {
+            println!("No changes to apply.");
+            return Ok(());
+        }
+
+        for step in &steps {
+            step.print();
+        }
+        println!();
+
+        if !self.yes && !prompt::confirm("Apply these changes?")? {
+            println!("Apply cancelled.");
+            return Ok(());
+        }
+
+        for step in steps {
+            match step.action {
+                Action::Create(args) => args.run().await?,
+                Action::Update {
+                    args,
+                    current,
+                    version,
+                    ..
+                } => args.print(args.execute(&client, &current, version).await?),
+                Action::Unchanged => continue,
+            }
+            println!();
+        }
+
+        Ok(())
+    }
+}
+
+/// Apply every step and print them as one JSON array, each with the resulting `config`. A
+/// failure stops the run; the steps up to and including it are still printed.
+async fn apply_json(client: &ApiClient, steps: Vec<Step>) -> Result<()> {
+    let mut results = Vec::new();
+    let mut outcome = Ok(());
+    for step in steps {
+        let mut result = step.to_json();
+        match step.action {
+            Action::Create(args) => match args.create_json().await {
+                Ok((created, verified)) => {
+                    result["config"] = created["config"].clone();
+                    if let Some(verify) = created.get("verify") {
+                        result["verify"] = verify.clone();
+                    }
+                    outcome = verified;
+                }
+                Err(e) => outcome = Err(e),
+            },
+            Action::Update {
+                args,
+                current,
+                version,
+                ..
+            } => match args.execute(client, &current, version).await {
+                Ok(config) => result["config"] = serde_json::to_value(config)?,
+                Err(e) => outcome = Err(e),
+            },
+            Action::Unchanged => {}
+        }
+
+        if let Err(e) = &outcome {
+            result["error"] = e.to_string().into();
+        }
+        results.push(result);
+        if outcome.is_err() {
+            break;
+        }
+    }
+
+    output::print_json(&results)?;
+    outcome
+}
@@ -0,0 +1,117 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use serde_json::json;
+use tokio::task::JoinSet;
+
+use crate::{
+    api::ApiClient,
+    command::deployments::{delete, services::Service},
+    output, prompt,
+};
+
+use super::{existing, Manifest};
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Destroy options")]
+pub struct DestroyArgs {
+    #[arg(short, long, default_value = "slot.toml")]
+    #[arg(value_name = "path")]
+    #[arg(help = "The manifest describing the deployments to delete.")]
+    pub file: PathBuf,
+
+    #[arg(long)]
+    #[arg(help = "Delete without asking for confirmation.")]
+    pub force: bool,
+}
+
+impl DestroyArgs {
+    pub async fn run(&self) -> Result<()> {
+        output::require_confirmed(self.force, "--force")?;
+        let manifest = Manifest::load(&self.file)?;
+        let client = ApiClient::new();
+        let existing = existing(&client).await?;
+
+        // Indexers go first so nothing is left pointing at a deleted sequencer.
+        let targets = manifest
+            .services()
+            .into_iter()
+            .rev()
+            .filter(|(project, service)| {
+                existing.contains(&(project.clone(), format!("{service:?}").to_lowercase()))
+            })
+            .collect::<Vec<_>>();
+
+        let json = output::is_json();
+        if targets.is_empty() && !json {
+            println!("Nothing to destroy.");
+            return Ok(());
+        }
+
+        if !json {
+            for (project, service) in &targets {
+                println!(
+                    "- {project} {} (delete)",
+                    format!("{service:?}").to_lowercase()
+                );
+            }
+            println!();
+        }
+
+        if !self.force {
+            println!("This permanently deletes {} deployment(s).", targets.len());
+            let answer = prompt::input("Type `destroy` to confirm:")?;
+            if answer != "destroy" {
+                println!("Destroy cancelled.");
+                return Ok(());
+            }
+        }
+
+        // Deletes run concurrently up to `--concurrency`, one service at a time so that the
+        // indexers are still gone before their sequencers.
+        let mut deleted = Vec::new();
+        let mut failed = Vec::new();
+        for service in [Service::Torii, Service::Katana] {
+            let mut tasks = JoinSet::new();
+            for (project, _) in targets.iter().filter(|(_, s)| *s == service) {
+                let client = client.clone();
+                let project = project.clone();
+                let service = service.clone();
+                tasks.spawn(async move {
+                    let result = delete::delete(&client, &project, &service).await;
+                    (project, result)
+                });
+            }
+
+            let name = format!("{service:?}").to_lowercase();
+            while let Some(task) = tasks.join_next().await {
+                match task? {
+                    (project, Ok(())) => {
+                        if !json {
+                            println!("Deleted {project} {name}.");
+                        }
+                        deleted.push(json!({ "project": project, "service": name }));
+                    }
+                    (project, Err(e)) => {
+                        eprintln!("Error: failed to delete {project} {name}: {e}");
+                        let error = e.to_string();
+                        failed.push(json!({ "project": project, "service": name, "error": error }));
+                    }
+                }
+            }
+        }
+
+        if json {
+            output::print_json(&json!({ "deleted": deleted, "failed": failed }))?;
+        }
+        if !failed.is_empty() {
+            return Err(anyhow!(
+                "{} deployment(s) could not be deleted.",
+                failed.len()
+            ));
+        }
+
+        Ok(())
+    }
+}
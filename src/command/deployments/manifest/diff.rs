@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::{api::ApiClient, output};
+
+use super::{plan, Action, Manifest};
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Diff options")]
+pub struct DiffArgs {
+    #[arg(short, long, default_value = "slot.toml")]
+    #[arg(value_name = "path")]
+    #[arg(help = "The manifest describing the desired deployments.")]
+    pub file: PathBuf,
+}
+
+impl DiffArgs {
+    pub async fn run(&self) -> Result<()> {
+        let manifest = Manifest::load(&self.file)?;
+        let client = ApiClient::new();
+        let steps = plan(&client, &manifest).await?;
+
+        if output::is_json() {
+            let steps: Vec<_> = steps.iter().map(|step| step.to_json()).collect();
+            return output::print_json(&steps);
+        }
+
+        for step in &steps {
+            step.print();
+        }
+
+        let count =
+            |action: fn(&Action) -> bool| steps.iter().filter(|s| action(&s.action)).count();
+        println!(
+            "\n{} to create, {} to update, {} unchanged.",
+            count(|a| matches!(a, Action::Create(_))),
+            count(|a| matches!(a, Action::Update { .. })),
+            count(|a| matches!(a, Action::Unchanged)),
+        );
+
+        Ok(())
+    }
+}
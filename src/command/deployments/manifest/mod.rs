@@ -0,0 +1,254 @@
+//! Deployments described in a TOML manifest, reconciled against the live ones.
+//!
+//! ```toml
+//! [[deployment]]
+//! project = "my-game"
+//! tier = "basic"
+//!
+//! [deployment.katana]
+//! version = "v0.7.0"
+//! block_time = 1000
+//!
+//! [deployment.torii]
+//! rpc = "https://api.cartridge.gg/x/my-game/katana"
+//! world = "0x0123"
+//! ```
+
+use std::{collections::HashSet, fs, path::Path};
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::api::ApiClient;
+
+use super::{
+    create::CreateArgs,
+    describe::describe_deployment::DescribeDeploymentDeployment,
+    list,
+    services::{
+        CreateServiceCommands, KatanaCreateArgs, Service, ToriiCreateArgs, UpdateServiceCommands,
+    },
+    update::UpdateArgs,
+    Tier,
+};
+
+pub use self::{apply::ApplyArgs, destroy::DestroyArgs, diff::DiffArgs};
+
+mod apply;
+mod destroy;
+mod diff;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    #[serde(default, rename = "deployment")]
+    pub deployments: Vec<ManifestDeployment>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestDeployment {
+    pub project: String,
+    /// New deployments default to the basic tier; existing ones keep theirs when unset.
+    pub tier: Option<Tier>,
+    pub katana: Option<KatanaCreateArgs>,
+    pub torii: Option<ToriiCreateArgs>,
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read {}: {e}", path.display()))?;
+        let manifest: Manifest = toml::from_str(&contents)
+            .map_err(|e| anyhow!("Invalid manifest {}: {e}", path.display()))?;
+
+        let mut projects = HashSet::new();
+        for deployment in &manifest.deployments {
+            if deployment.katana.is_none() && deployment.torii.is_none() {
+                return Err(anyhow!(
+                    "Project {} in {} has no katana or torii service.",
+                    deployment.project,
+                    path.display()
+                ));
+            }
+
+            if !projects.insert(&deployment.project) {
+                return Err(anyhow!(
+                    "Project {} is declared more than once in {}.",
+                    deployment.project,
+                    path.display()
+                ));
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Every (project, service) pair in the manifest, katana before torii so an indexer
+    /// can point at a sequencer created in the same run.
+    pub fn services(&self) -> Vec<(String, Service)> {
+        let mut services = Vec::new();
+        for deployment in &self.deployments {
+            if deployment.katana.is_some() {
+                services.push((deployment.project.clone(), Service::Katana));
+            }
+            if deployment.torii.is_some() {
+                services.push((deployment.project.clone(), Service::Torii));
+            }
+        }
+        services
+    }
+}
+
+/// What reconciling a single service of the manifest will do.
+pub enum Action {
+    Create(CreateArgs),
+    Update {
+        args: UpdateArgs,
+        current: Box<DescribeDeploymentDeployment>,
+        version: Option<String>,
+        changes: Vec<String>,
+    },
+    Unchanged,
+}
+
+pub struct Step {
+    pub project: String,
+    pub service: Service,
+    pub action: Action,
+}
+
+impl Step {
+    pub fn print(&self) {
+        let service = format!("{:?}", self.service).to_lowercase();
+        match &self.action {
+            Action::Create(_) => println!("+ {} {service} (create)", self.project),
+            Action::Update { changes, .. } => {
+                println!("~ {} {service} (update)", self.project);
+                for change in changes {
+                    println!("  {change}");
+                }
+            }
+            Action::Unchanged => println!("  {} {service} (unchanged)", self.project),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let (action, changes) = match &self.action {
+            Action::Create(_) => ("create", Vec::new()),
+            Action::Update { changes, .. } => {
+                ("update", changes.iter().map(|c| c.trim()).collect())
+            }
+            Action::Unchanged => ("unchanged", Vec::new()),
+        };
+        json!({
+            "project": self.project,
+            "service": format!("{:?}", self.service).to_lowercase(),
+            "action": action,
+            "changes": changes,
+        })
+    }
+
+    pub fn is_change(&self) -> bool {
+        !matches!(self.action, Action::Unchanged)
+    }
+}
+
+/// Compare the manifest with the live deployments and work out what has to change.
+pub async fn plan(client: &ApiClient, manifest: &Manifest) -> Result<Vec<Step>> {
+    let existing = existing(client).await?;
+
+    let mut steps = Vec::new();
+    for deployment in &manifest.deployments {
+        if let Some(katana) = &deployment.katana {
+            let create = CreateServiceCommands::Katana(katana.clone());
+            let update = UpdateServiceCommands::Katana(katana.into());
+            let step = step(
+                client,
+                &existing,
+                deployment,
+                Service::Katana,
+                create,
+                update,
+            );
+            steps.push(step.await?);
+        }
+        if let Some(torii) = &deployment.torii {
+            let create = CreateServiceCommands::Torii(torii.clone());
+            let update = UpdateServiceCommands::Torii(torii.into());
+            let step = step(
+                client,
+                &existing,
+                deployment,
+                Service::Torii,
+                create,
+                update,
+            );
+            steps.push(step.await?);
+        }
+    }
+
+    Ok(steps)
+}
+
+async fn step(
+    client: &ApiClient,
+    existing: &HashSet<(String, String)>,
+    deployment: &ManifestDeployment,
+    service: Service,
+    create_commands: CreateServiceCommands,
+    update_commands: UpdateServiceCommands,
+) -> Result<Step> {
+    let project = deployment.project.clone();
+    let key = (project.clone(), format!("{service:?}").to_lowercase());
+
+    if !existing.contains(&key) {
+        let args = CreateArgs {
+            project: project.clone(),
+            tier: deployment.tier.clone().unwrap_or_default(),
+            create_commands,
+        };
+        return Ok(Step {
+            project,
+            service,
+            action: Action::Create(args),
+        });
+    }
+
+    let args = UpdateArgs {
+        project: project.clone(),
+        tier: deployment.tier.clone(),
+        yes: true,
+        update_commands,
+    };
+    let current = args.current(client).await?;
+    let version = args.version(&current);
+    let changes = args.changes(&current, version.clone());
+
+    let action = if changes.is_empty() {
+        Action::Unchanged
+    } else {
+        Action::Update {
+            args,
+            current: Box::new(current),
+            version,
+            changes,
+        }
+    };
+
+    Ok(Step {
+        project,
+        service,
+        action,
+    })
+}
+
+/// The (project, service) pairs that are currently deployed.
+pub async fn existing(client: &ApiClient) -> Result<HashSet<(String, String)>> {
+    Ok(list::fetch(client, None, None)
+        .await?
+        .into_iter()
+        .map(|entry| (entry.project, entry.service))
+        .collect())
+}
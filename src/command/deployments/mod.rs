@@ -1,19 +1,40 @@
 use anyhow::Result;
 use clap::Subcommand;
+use serde::{de::DeserializeOwned, Serialize};
 
 use self::{
-    create::CreateArgs, delete::DeleteArgs, describe::DescribeArgs, fork::ForkArgs, list::ListArgs,
-    logs::LogsArgs, update::UpdateArgs,
+    accounts::AccountsArgs,
+    config::ConfigCommands,
+    create::CreateArgs,
+    delete::DeleteArgs,
+    describe::DescribeArgs,
+    faucet::FaucetArgs,
+    fork::ForkArgs,
+    forward::ForwardArgs,
+    list::ListArgs,
+    logs::LogsArgs,
+    manifest::{ApplyArgs, DestroyArgs, DiffArgs},
+    scale::ScaleArgs,
+    update::UpdateArgs,
+    wait::WaitArgs,
 };
 
+mod accounts;
+mod config;
 mod create;
-mod delete;
-mod describe;
+pub(crate) mod delete;
+pub(crate) mod describe;
+mod faucet;
 mod fork;
-mod list;
-mod logs;
-mod services;
+mod forward;
+pub(crate) mod list;
+pub(crate) mod logs;
+pub(crate) mod manifest;
+mod scale;
+pub(crate) mod services;
 mod update;
+mod wait;
+mod watch;
 
 type Long = u64;
 
@@ -25,14 +46,33 @@ pub enum Deployments {
     Delete(DeleteArgs),
     #[command(about = "Update a deployment.")]
     Update(UpdateArgs),
+    #[command(about = "Change the resource tier of a deployment.")]
+    Scale(ScaleArgs),
     #[command(about = "Fork a deployment.")]
     Fork(ForkArgs),
     #[command(about = "Describe a deployment's configuration.")]
     Describe(DescribeArgs),
+    #[command(subcommand)]
+    #[command(about = "View or edit the live configuration of a deployment.")]
+    Config(ConfigCommands),
     #[command(about = "List all deployments.", aliases = ["ls"])]
     List(ListArgs),
     #[command(about = "Fetch logs for a deployment.")]
     Logs(LogsArgs),
+    #[command(about = "Proxy a local port to a deployment's endpoint.")]
+    Forward(ForwardArgs),
+    #[command(about = "Wait until a deployment's endpoint is healthy.")]
+    Wait(WaitArgs),
+    #[command(about = "Print the prefunded accounts of a katana deployment.")]
+    Accounts(AccountsArgs),
+    #[command(about = "Send tokens to an address from a katana deployment's prefunded account.")]
+    Faucet(FaucetArgs),
+    #[command(about = "Create or update deployments to match a manifest.")]
+    Apply(ApplyArgs),
+    #[command(about = "Show what applying a manifest would change.")]
+    Diff(DiffArgs),
+    #[command(about = "Delete the deployments declared in a manifest.")]
+    Destroy(DestroyArgs),
 }
 
 impl Deployments {
@@ -41,15 +81,83 @@ impl Deployments {
             Deployments::Create(args) => args.run().await,
             Deployments::Delete(args) => args.run().await,
             Deployments::Update(args) => args.run().await,
+            Deployments::Scale(args) => args.run().await,
             Deployments::Fork(args) => args.run().await,
             Deployments::Describe(args) => args.run().await,
+            Deployments::Config(cmd) => cmd.run().await,
             Deployments::List(args) => args.run().await,
             Deployments::Logs(args) => args.run().await,
+            Deployments::Forward(args) => args.run().await,
+            Deployments::Wait(args) => args.run().await,
+            Deployments::Accounts(args) => args.run().await,
+            Deployments::Faucet(args) => args.run().await,
+            Deployments::Apply(args) => args.run().await,
+            Deployments::Diff(args) => args.run().await,
+            Deployments::Destroy(args) => args.run().await,
         }
     }
 }
 
-#[derive(clap::ValueEnum, Clone, Debug, serde::Serialize)]
+#[derive(clap::ValueEnum, Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Tier {
+    #[default]
     Basic,
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl Tier {
+    /// This tier as the `DeploymentTier` of a generated query module.
+    pub(super) fn to_api<T: DeserializeOwned>(&self) -> T {
+        convert_tier(self)
+    }
+}
+
+/// Convert between tier enums by name. The generated ones all use the schema's names, as does
+/// [`Tier`] once serialized, so there's no per-module mapping to keep in sync; names a
+/// module doesn't know yet end up in its `Other` variant.
+pub(super) fn convert_tier<T: DeserializeOwned>(tier: &impl Serialize) -> T {
+    let name = serde_json::to_value(tier).expect("tiers serialize to their name");
+    serde_json::from_value(name).expect("generated tier enums accept any name")
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::ValueEnum;
+
+    use super::*;
+
+    #[test]
+    fn every_tier_maps_to_a_known_api_tier() {
+        use create::create_deployment::DeploymentTier as Create;
+        use fork::fork_deployment::DeploymentTier as Fork;
+        use update::update_deployment::DeploymentTier as Update;
+
+        for tier in Tier::value_variants() {
+            let name = tier.to_possible_value().unwrap().get_name().to_string();
+
+            let create: Create = tier.to_api();
+            assert!(!matches!(create, Create::Other(_)), "{name}");
+            assert!(!matches!(tier.to_api::<Fork>(), Fork::Other(_)), "{name}");
+            let update: Update = tier.to_api();
+            assert!(!matches!(update, Update::Other(_)), "{name}");
+            assert_eq!(serde_json::to_value(&update).unwrap(), name.as_str());
+        }
+    }
+
+    #[test]
+    fn live_tiers_carry_over_to_updates_including_unknown_ones() {
+        use describe::describe_deployment::DeploymentTier as Live;
+        use update::update_deployment::DeploymentTier as Update;
+
+        let update: Update = convert_tier(&Live::epic);
+        assert!(matches!(update, Update::epic));
+
+        let update: Update = convert_tier(&Live::Other("mythic".to_string()));
+        assert!(matches!(update, Update::Other(tier) if tier == "mythic"));
+    }
 }
@@ -0,0 +1,46 @@
+use anyhow::Result;
+use clap::Args;
+
+use super::{
+    services::{Service, UpdateServiceCommands},
+    update::UpdateArgs,
+    Tier,
+};
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Scale options")]
+pub struct ScaleArgs {
+    #[arg(help = "The name of the project.")]
+    pub project: String,
+
+    #[arg(help = "The name of the service.")]
+    pub service: Service,
+
+    #[arg(short, long)]
+    #[arg(value_name = "tier")]
+    #[arg(help = "The tier to move the deployment to.")]
+    pub tier: Tier,
+
+    #[arg(short, long)]
+    #[arg(help = "Scale without asking for confirmation.")]
+    pub yes: bool,
+}
+
+impl ScaleArgs {
+    pub async fn run(&self) -> Result<()> {
+        // A tier-only update: every service option is left as it is.
+        let update_commands = match self.service {
+            Service::Katana => UpdateServiceCommands::Katana(Default::default()),
+            Service::Torii => UpdateServiceCommands::Torii(Default::default()),
+        };
+
+        UpdateArgs {
+            project: self.project.clone(),
+            tier: Some(self.tier.clone()),
+            yes: self.yes,
+            update_commands,
+        }
+        .run()
+        .await
+    }
+}
@@ -1,8 +1,18 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{anyhow, Result};
 use clap::Args;
+use url::Url;
 
-#[derive(Debug, Args, serde::Serialize)]
+#[derive(Clone, Debug, Default, Args, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
 #[command(next_help_heading = "Katana create options")]
 pub struct KatanaCreateArgs {
+    #[arg(long, value_name = "path")]
+    #[arg(help = "TOML file with katana options. Command-line flags take precedence.")]
+    #[serde(skip)]
+    pub config: Option<PathBuf>,
+
     #[arg(long, short, value_name = "version")]
     #[arg(help = "Service version to use.")]
     pub version: Option<String>,
@@ -12,11 +22,11 @@ pub struct KatanaCreateArgs {
     pub block_time: Option<i64>,
 
     #[arg(long, value_name = "fork_rpc_url")]
-    #[arg(help = "Fork RPC URL.")]
+    #[arg(help = "RPC URL of a network, e.g. mainnet or sepolia, to fork state from.")]
     pub fork_rpc_url: Option<String>,
 
-    #[arg(long, value_name = "fork_block_number")]
-    #[arg(help = "Fork block number.")]
+    #[arg(long, visible_alias = "fork-block", value_name = "fork_block_number")]
+    #[arg(help = "Block to fork from. Defaults to the latest block of the fork RPC.")]
     pub fork_block_number: Option<u64>,
 
     #[arg(long, short, value_name = "seed")]
@@ -48,22 +58,75 @@ pub struct KatanaCreateArgs {
     pub chain_id: Option<String>,
 }
 
-#[derive(Debug, Args, serde::Serialize)]
+impl KatanaCreateArgs {
+    /// Fill in options not given on the command line from the `--config` file, then check
+    /// that the merged options are consistent.
+    pub fn resolve(&self) -> Result<Self> {
+        let Some(path) = &self.config else {
+            self.validate()?;
+            return Ok(self.clone());
+        };
+
+        let contents = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read {}: {e}", path.display()))?;
+        let file: KatanaCreateArgs = toml::from_str(&contents)
+            .map_err(|e| anyhow!("Invalid katana config {}: {e}", path.display()))?;
+
+        let resolved = Self {
+            config: self.config.clone(),
+            version: self.version.clone().or(file.version),
+            block_time: self.block_time.or(file.block_time),
+            fork_rpc_url: self.fork_rpc_url.clone().or(file.fork_rpc_url),
+            fork_block_number: self.fork_block_number.or(file.fork_block_number),
+            seed: self.seed.clone().or(file.seed),
+            accounts: self.accounts.or(file.accounts),
+            invoke_max_steps: self.invoke_max_steps.or(file.invoke_max_steps),
+            validate_max_steps: self.validate_max_steps.or(file.validate_max_steps),
+            disable_fee: self.disable_fee.or(file.disable_fee),
+            gas_price: self.gas_price.or(file.gas_price),
+            chain_id: self.chain_id.clone().or(file.chain_id),
+        };
+        resolved.validate()?;
+
+        Ok(resolved)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if let Some(url) = &self.fork_rpc_url {
+            let parsed = Url::parse(url).map_err(|e| anyhow!("Invalid fork RPC URL {url}: {e}"))?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(anyhow!("Fork RPC URL {url} must use http or https."));
+            }
+        } else if self.fork_block_number.is_some() {
+            return Err(anyhow!(
+                "A fork block number needs a fork RPC URL to fork from."
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Args, serde::Serialize)]
 #[command(next_help_heading = "Katana update options")]
 pub struct KatanaUpdateArgs {
     #[arg(long, short, value_name = "version")]
     #[arg(help = "Service version to use.")]
     pub version: Option<String>,
 
+    #[arg(long, conflicts_with = "version")]
+    #[arg(help = "Upgrade to the latest version of the service.")]
+    pub latest: bool,
+
     #[arg(long, short, value_name = "block_time")]
     #[arg(help = "Block time.")]
     pub block_time: Option<i64>,
 
-    #[arg(long, short, value_name = "fork_rpc_url")]
+    #[arg(long, value_name = "fork_rpc_url")]
     #[arg(help = "Fork RPC URL.")]
     pub fork_rpc_url: Option<String>,
 
-    #[arg(long, short, value_name = "fork_block_number")]
+    #[arg(long, visible_alias = "fork-block", value_name = "fork_block_number")]
     #[arg(help = "Fork Block Number.")]
     pub fork_block_number: Option<u64>,
 
@@ -94,3 +157,20 @@ pub struct KatanaForkArgs {
     #[arg(help = "Specify block number to fork. (latests if not provided)")]
     pub fork_block_number: Option<u64>,
 }
+
+impl From<&KatanaCreateArgs> for KatanaUpdateArgs {
+    /// Keep the create options that can also be changed on a live deployment.
+    fn from(args: &KatanaCreateArgs) -> Self {
+        Self {
+            version: args.version.clone(),
+            latest: false,
+            block_time: args.block_time,
+            fork_rpc_url: args.fork_rpc_url.clone(),
+            fork_block_number: args.fork_block_number,
+            invoke_max_steps: args.invoke_max_steps,
+            validate_max_steps: args.validate_max_steps,
+            disable_fee: args.disable_fee,
+            gas_price: args.gas_price,
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use clap::{Subcommand, ValueEnum};
 
-use self::{
+pub use self::{
     katana::{KatanaCreateArgs, KatanaForkArgs, KatanaUpdateArgs},
     torii::{ToriiCreateArgs, ToriiUpdateArgs},
 };
@@ -36,7 +36,7 @@ pub enum ForkServiceCommands {
     // Torii(ToriiUpdateArgs),
 }
 
-#[derive(Clone, Debug, ValueEnum, serde::Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, ValueEnum, serde::Serialize)]
 pub enum Service {
     Katana,
     Torii,
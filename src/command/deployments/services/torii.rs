@@ -1,9 +1,18 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{anyhow, Result};
 use clap::Args;
 use starknet::core::types::FieldElement;
 
-#[derive(Clone, Debug, Args, serde::Serialize)]
+#[derive(Clone, Debug, Default, Args, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
 #[command(next_help_heading = "Torii create options")]
 pub struct ToriiCreateArgs {
+    #[arg(long, value_name = "path")]
+    #[arg(help = "TOML file with torii options. Command-line flags take precedence.")]
+    #[serde(skip)]
+    pub config: Option<PathBuf>,
+
     #[arg(long, short, value_name = "version")]
     #[arg(help = "Service version to use.")]
     pub version: Option<String>,
@@ -11,22 +20,83 @@ pub struct ToriiCreateArgs {
     #[arg(long)]
     #[arg(value_name = "rpc")]
     #[arg(help = "The Starknet RPC endpoint.")]
-    pub rpc: String,
+    pub rpc: Option<String>,
 
     #[arg(long)]
     #[arg(value_name = "world")]
     #[arg(help = "World address.")]
-    pub world: FieldElement,
+    #[serde(serialize_with = "serialize_hex")]
+    pub world: Option<FieldElement>,
 
     #[arg(short, long)]
     #[arg(help = "Specify a block to start indexing from.")]
-    pub start_block: i64,
+    pub start_block: Option<i64>,
+
+    #[arg(long)]
+    #[arg(help = "After creating, check the GraphQL endpoint answers and the world gets indexed.")]
+    #[serde(skip)]
+    pub verify: bool,
+
+    #[arg(long, default_value = "300", requires = "verify")]
+    #[arg(value_name = "seconds")]
+    #[arg(help = "How long `--verify` waits for indexing to start.")]
+    #[serde(skip)]
+    pub verify_timeout: u64,
+}
+
+impl ToriiCreateArgs {
+    /// Fill in options not given on the command line from the `--config` file.
+    pub fn resolve(&self) -> Result<Self> {
+        let Some(path) = &self.config else {
+            return Ok(self.clone());
+        };
+
+        let contents = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read {}: {e}", path.display()))?;
+        let file: ToriiCreateArgs = toml::from_str(&contents)
+            .map_err(|e| anyhow!("Invalid torii config {}: {e}", path.display()))?;
+
+        Ok(Self {
+            config: self.config.clone(),
+            version: self.version.clone().or(file.version),
+            rpc: self.rpc.clone().or(file.rpc),
+            world: self.world.or(file.world),
+            start_block: self.start_block.or(file.start_block),
+            verify: self.verify,
+            verify_timeout: self.verify_timeout,
+        })
+    }
+}
+
+/// Write addresses the way they're passed on the command line rather than as decimals.
+fn serialize_hex<S: serde::Serializer>(
+    value: &Option<FieldElement>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => serializer.serialize_str(&format!("{value:#x}")),
+        None => serializer.serialize_none(),
+    }
 }
 
-#[derive(Clone, Debug, Args, serde::Serialize)]
+#[derive(Clone, Debug, Default, Args, serde::Serialize)]
 #[command(next_help_heading = "Torii update options")]
 pub struct ToriiUpdateArgs {
     #[arg(long, short, value_name = "version")]
     #[arg(help = "Service version to use.")]
     pub version: Option<String>,
+
+    #[arg(long, conflicts_with = "version")]
+    #[arg(help = "Upgrade to the latest version of the service.")]
+    pub latest: bool,
+}
+
+impl From<&ToriiCreateArgs> for ToriiUpdateArgs {
+    /// Keep the create options that can also be changed on a live deployment.
+    fn from(args: &ToriiCreateArgs) -> Self {
+        Self {
+            version: args.version.clone(),
+            latest: false,
+        }
+    }
 }
@@ -1,56 +1,170 @@
 #![allow(clippy::enum_variant_names)]
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use clap::Args;
-use graphql_client::{GraphQLQuery, Response};
+use graphql_client::GraphQLQuery;
+use serde_json::{json, Value};
+
+use std::fmt::Display;
 
 use self::update_deployment::UpdateServiceInput;
 use crate::{
     api::ApiClient,
     command::deployments::update::update_deployment::{
         DeploymentService, DeploymentTier,
-        UpdateDeploymentUpdateDeployment::{KatanaConfig, ToriiConfig},
+        UpdateDeploymentUpdateDeployment::{self, KatanaConfig, ToriiConfig},
         UpdateKatanaConfigInput, UpdateServiceConfigInput, Variables,
     },
+    output, prompt,
 };
 
-use super::services::UpdateServiceCommands;
-
-type Long = u64;
+use super::{
+    convert_tier,
+    describe::{self, describe_deployment},
+    services::{Service, UpdateServiceCommands},
+    Long, Tier,
+};
 
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "schema.json",
     query_path = "src/command/deployments/update.graphql",
-    response_derives = "Debug"
+    response_derives = "Debug, Serialize"
 )]
 pub struct UpdateDeployment;
 
-#[derive(clap::ValueEnum, Clone, Debug, serde::Serialize)]
-pub enum Tier {
-    Basic,
-}
-
 #[derive(Debug, Args)]
 #[command(next_help_heading = "Update options")]
 pub struct UpdateArgs {
     #[arg(help = "The name of the project.")]
     pub project: String,
-    #[arg(short, long, default_value = "basic")]
+    #[arg(short, long)]
     #[arg(value_name = "tier")]
-    #[arg(help = "Deployment tier.")]
-    pub tier: Tier,
+    #[arg(help = "Deployment tier. Defaults to the current tier.")]
+    pub tier: Option<Tier>,
+
+    #[arg(short, long, global = true)]
+    #[arg(help = "Apply the update without asking for confirmation.")]
+    pub yes: bool,
 
     #[command(subcommand)]
-    update_commands: UpdateServiceCommands,
+    pub update_commands: UpdateServiceCommands,
 }
 
 impl UpdateArgs {
     pub async fn run(&self) -> Result<()> {
+        output::require_confirmed(self.yes, "--yes")?;
+        let client = ApiClient::new();
+        let current = self.current(&client).await?;
+        let version = self.version(&current);
+
+        let changes = self.changes(&current, version.clone());
+        if output::is_json() {
+            let config = if changes.is_empty() {
+                None
+            } else {
+                Some(self.execute(&client, &current, version).await?)
+            };
+            return output::print_json(&self.to_json(&changes, config));
+        }
+
+        if changes.is_empty() {
+            println!("No changes to apply.");
+            return Ok(());
+        }
+
+        println!("Changes to {}:", self.project);
+        for change in &changes {
+            println!("{change}");
+        }
+        println!();
+
+        if !self.yes && !prompt::confirm("Apply these changes?")? {
+            println!("Update cancelled.");
+            return Ok(());
+        }
+
+        let config = self.execute(&client, &current, version).await?;
+        self.print(config);
+        Ok(())
+    }
+
+    /// The JSON result: what changed, and the new config if the update was sent.
+    pub(super) fn to_json(
+        &self,
+        changes: &[String],
+        config: Option<UpdateDeploymentUpdateDeployment>,
+    ) -> Value {
+        let changes: Vec<&str> = changes.iter().map(|change| change.trim()).collect();
+        json!({
+            "project": self.project,
+            "service": self.service_name(),
+            "changes": changes,
+            "updated": config.is_some(),
+            "config": config,
+        })
+    }
+
+    pub(super) fn print(&self, config: UpdateDeploymentUpdateDeployment) {
+        println!("Update success 🚀");
+        match config {
+            ToriiConfig(config) => {
+                println!("\nConfiguration:");
+                println!("  World: {}", config.world);
+                println!("  RPC: {}", config.rpc);
+                println!("  Start Block: {}", config.start_block);
+                println!("\nEndpoints:");
+                println!("  GRAPHQL: {}", config.graphql);
+                println!("  GRPC: {}", config.grpc);
+            }
+            KatanaConfig(config) => {
+                println!("\nEndpoints:");
+                println!("  RPC: {}", config.rpc);
+            }
+        }
+
+        println!(
+            "\nStream logs with `slot deployments logs {} {} -f`",
+            self.project,
+            self.service_name()
+        );
+    }
+
+    fn service_name(&self) -> &'static str {
+        match &self.update_commands {
+            UpdateServiceCommands::Katana(_) => "katana",
+            UpdateServiceCommands::Torii(_) => "torii",
+        }
+    }
+
+    /// The version to move to, resolving `--latest` against the live deployment.
+    pub(super) fn version(
+        &self,
+        current: &describe_deployment::DescribeDeploymentDeployment,
+    ) -> Option<String> {
+        match &self.update_commands {
+            UpdateServiceCommands::Katana(config) if config.latest => {
+                Some(current.service.default_version.clone())
+            }
+            UpdateServiceCommands::Torii(config) if config.latest => {
+                Some(current.service.default_version.clone())
+            }
+            UpdateServiceCommands::Katana(config) => config.version.clone(),
+            UpdateServiceCommands::Torii(config) => config.version.clone(),
+        }
+    }
+
+    /// Send the update without previewing or confirming it.
+    pub(super) async fn execute(
+        &self,
+        client: &ApiClient,
+        current: &describe_deployment::DescribeDeploymentDeployment,
+        version: Option<String>,
+    ) -> Result<UpdateDeploymentUpdateDeployment> {
         let service = match &self.update_commands {
             UpdateServiceCommands::Katana(config) => UpdateServiceInput {
                 type_: DeploymentService::katana,
-                version: config.version.clone(),
+                version,
                 config: Some(UpdateServiceConfigInput {
                     katana: Some(UpdateKatanaConfigInput {
                         block_time: config.block_time,
@@ -63,63 +177,239 @@ impl UpdateArgs {
                     }),
                 }),
             },
-            UpdateServiceCommands::Torii(config) => UpdateServiceInput {
+            UpdateServiceCommands::Torii(_) => UpdateServiceInput {
                 type_: DeploymentService::torii,
-                version: config.version.clone(),
+                version,
                 config: Some(UpdateServiceConfigInput { katana: None }),
             },
         };
 
-        let tier = match &self.tier {
-            Tier::Basic => DeploymentTier::basic,
+        let tier: DeploymentTier = match &self.tier {
+            Some(tier) => tier.to_api(),
+            // Keep the live tier when the update doesn't ask for a new one.
+            None => convert_tier(&current.tier),
         };
 
-        let request_body = UpdateDeployment::build_query(Variables {
-            project: self.project.clone(),
+        let data = client
+            .query::<UpdateDeployment>(Variables {
+                project: self.project.clone(),
+                tier,
+                service,
+                wait: Some(true),
+            })
+            .await
+            .map_err(|e| anyhow!("Update of {} failed: {e}", self.project))?;
+
+        Ok(data.update_deployment)
+    }
+
+    /// Fetch the live deployment so the update can be previewed against it.
+    pub(super) async fn current(
+        &self,
+        client: &ApiClient,
+    ) -> Result<describe_deployment::DescribeDeploymentDeployment> {
+        let service = match &self.update_commands {
+            UpdateServiceCommands::Katana(_) => Service::Katana,
+            UpdateServiceCommands::Torii(_) => Service::Torii,
+        };
+
+        describe::fetch(client, &self.project, &service).await
+    }
+
+    pub(super) fn changes(
+        &self,
+        current: &describe_deployment::DescribeDeploymentDeployment,
+        version: Option<String>,
+    ) -> Vec<String> {
+        use describe_deployment::DescribeDeploymentDeploymentConfig as Config;
+
+        let mut changes = Vec::new();
+
+        let tier = self
+            .tier
+            .as_ref()
+            .map(|tier| format!("{tier:?}").to_lowercase());
+        diff(
+            &mut changes,
+            "Tier",
+            Some(format!("{:?}", current.tier)),
             tier,
-            service,
-            wait: Some(true),
-        });
+        );
 
-        let client = ApiClient::new();
-        let res: Response<update_deployment::ResponseData> = client.post(&request_body).await?;
-        if let Some(errors) = res.errors.clone() {
-            for err in errors {
-                println!("Error: {}", err.message);
-            }
+        let current_version = match &current.config {
+            Config::KatanaConfig(config) => config.version.clone(),
+            Config::ToriiConfig(config) => config.version.clone(),
+        };
+        diff(&mut changes, "Version", Some(current_version), version);
 
-            return Ok(());
+        if let (UpdateServiceCommands::Katana(new), Config::KatanaConfig(old)) =
+            (&self.update_commands, &current.config)
+        {
+            diff(&mut changes, "Block Time", old.block_time, new.block_time);
+            diff(
+                &mut changes,
+                "Fork RPC URL",
+                old.fork_rpc_url.clone(),
+                new.fork_rpc_url.clone(),
+            );
+            diff(
+                &mut changes,
+                "Fork Block Number",
+                old.fork_block_number,
+                new.fork_block_number,
+            );
+            diff(
+                &mut changes,
+                "Invoke Max Steps",
+                old.invoke_max_steps,
+                new.invoke_max_steps,
+            );
+            diff(
+                &mut changes,
+                "Validate Max Steps",
+                old.validate_max_steps,
+                new.validate_max_steps,
+            );
+            diff(
+                &mut changes,
+                "Disable Fee",
+                old.disable_fee,
+                new.disable_fee,
+            );
+            diff(&mut changes, "Gas Price", old.gas_price, new.gas_price);
         }
 
-        if let Some(data) = res.data {
-            println!("Update success 🚀");
-            match data.update_deployment {
-                ToriiConfig(config) => {
-                    println!("\nConfiguration:");
-                    println!("  World: {}", config.world);
-                    println!("  RPC: {}", config.rpc);
-                    println!("  Start Block: {}", config.start_block);
-                    println!("\nEndpoints:");
-                    println!("  GRAPHQL: {}", config.graphql);
-                    println!("  GRPC: {}", config.grpc);
-                }
-                KatanaConfig(config) => {
-                    println!("\nEndpoints:");
-                    println!("  RPC: {}", config.rpc);
-                }
-            }
+        changes
+    }
+}
+
+/// Record `label` as changed when a new value is requested that differs from the current one.
+fn diff<T: Display + PartialEq>(
+    changes: &mut Vec<String>,
+    label: &str,
+    current: Option<T>,
+    new: Option<T>,
+) {
+    let Some(new) = new else {
+        return;
+    };
+
+    if current.as_ref() != Some(&new) {
+        let current = current.map_or_else(|| String::from("unset"), |c| c.to_string());
+        changes.push(format!("  {label}: {current} -> {new}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{api::MockApi, command::deployments::services::KatanaUpdateArgs};
+
+    fn update(tier: Option<Tier>, katana: KatanaUpdateArgs) -> UpdateArgs {
+        UpdateArgs {
+            project: "my-project".to_string(),
+            tier,
+            yes: true,
+            update_commands: UpdateServiceCommands::Katana(katana),
         }
+    }
 
-        let service = match &self.update_commands {
-            UpdateServiceCommands::Katana(_) => "katana",
-            UpdateServiceCommands::Torii(_) => "torii",
-        };
+    async fn live(
+        api: &MockApi,
+        args: &UpdateArgs,
+    ) -> describe_deployment::DescribeDeploymentDeployment {
+        api.respond(
+            "DescribeDeployment",
+            json!({ "deployment": {
+                "id": "1",
+                "project": "my-project",
+                "branch": null,
+                "tier": "basic",
+                "autoUpgrade": false,
+                "createdAt": "2024-01-01T00:00:00Z",
+                "updatedAt": "2024-01-01T00:00:00Z",
+                "service": { "id": "katana", "versions": ["v1.0.0", "v1.1.0"], "defaultVersion": "v1.1.0" },
+                "config": {
+                    "__typename": "KatanaConfig",
+                    "version": "v1.0.0",
+                    "rpc": "https://api.cartridge.gg/x/my-project/katana",
+                    "blockTime": 1000,
+                    "forkRpcUrl": null,
+                    "forkBlockNumber": null,
+                    "accounts": 10,
+                    "invokeMaxSteps": null,
+                    "validateMaxSteps": null,
+                    "disableFee": false,
+                    "gasPrice": null,
+                    "chainId": "KATANA",
+                },
+            } }),
+        );
+        args.current(&ApiClient::with_api(api.clone()))
+            .await
+            .unwrap()
+    }
 
-        println!(
-            "\nStream logs with `slot deployments logs {} {service} -f`",
-            self.project
+    #[tokio::test]
+    async fn changes_list_only_what_differs_from_the_live_deployment() {
+        let api = MockApi::new();
+        let args = update(
+            Some(Tier::Epic),
+            KatanaUpdateArgs {
+                block_time: Some(1000),
+                disable_fee: Some(true),
+                gas_price: Some(5),
+                ..Default::default()
+            },
         );
+        let current = live(&api, &args).await;
 
-        Ok(())
+        assert_eq!(
+            args.changes(&current, args.version(&current)),
+            [
+                "  Tier: basic -> epic",
+                "  Disable Fee: false -> true",
+                "  Gas Price: unset -> 5",
+            ]
+        );
+        assert_eq!(api.requests()[0].body["variables"]["service"], "katana");
+    }
+
+    #[tokio::test]
+    async fn latest_resolves_to_the_default_version() {
+        let api = MockApi::new();
+        let args = update(
+            None,
+            KatanaUpdateArgs {
+                latest: true,
+                ..Default::default()
+            },
+        );
+        let current = live(&api, &args).await;
+
+        let version = args.version(&current);
+        assert_eq!(version.as_deref(), Some("v1.1.0"));
+        assert_eq!(
+            args.changes(&current, version),
+            ["  Version: v1.0.0 -> v1.1.0"]
+        );
+    }
+
+    #[tokio::test]
+    async fn matching_values_are_not_changes() {
+        let api = MockApi::new();
+        let args = update(
+            Some(Tier::Basic),
+            KatanaUpdateArgs {
+                version: Some("v1.0.0".to_string()),
+                block_time: Some(1000),
+                ..Default::default()
+            },
+        );
+        let current = live(&api, &args).await;
+
+        assert!(args.changes(&current, args.version(&current)).is_empty());
     }
 }
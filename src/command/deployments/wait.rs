@@ -0,0 +1,132 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use serde_json::{json, Value};
+use tokio::time::{sleep, Instant};
+
+use crate::{api::ApiClient, output};
+
+use super::{
+    describe::{self, describe_deployment::DescribeDeploymentDeploymentConfig},
+    services::Service,
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Wait options")]
+pub struct WaitArgs {
+    #[arg(help = "The name of the project.")]
+    pub project: String,
+
+    #[arg(help = "The name of the service.")]
+    pub service: Service,
+
+    #[arg(long, default_value = "300")]
+    #[arg(value_name = "seconds")]
+    #[arg(help = "How long to wait for the deployment to become healthy before failing.")]
+    pub timeout: u64,
+
+    #[arg(long, default_value = "5")]
+    #[arg(value_name = "seconds")]
+    #[arg(help = "How long to wait between health checks.")]
+    pub interval: u64,
+
+    #[arg(long)]
+    #[arg(help = "For torii, also wait until the world has at least one indexed model.")]
+    pub indexed: bool,
+}
+
+impl WaitArgs {
+    pub async fn run(&self) -> Result<()> {
+        let endpoint = self.wait(output::is_json()).await?;
+        if output::is_json() {
+            let service = format!("{:?}", self.service).to_lowercase();
+            output::print_json(&json!({
+                "project": self.project,
+                "service": service,
+                "healthy": true,
+                "endpoint": endpoint,
+            }))?;
+        }
+        Ok(())
+    }
+
+    /// Poll until the deployment is healthy, returning the endpoint that answered. `quiet`
+    /// leaves the progress messages out, for callers that report the result themselves.
+    pub async fn wait(&self, quiet: bool) -> Result<String> {
+        let client = ApiClient::new();
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()?;
+        let interval = Duration::from_secs(self.interval);
+        let deadline = Instant::now() + Duration::from_secs(self.timeout);
+        let service = format!("{:?}", self.service).to_lowercase();
+
+        if !quiet {
+            println!(
+                "Waiting for {} {service} to become healthy...",
+                self.project
+            );
+        }
+        loop {
+            let err = match self.check(&client, &http).await {
+                Ok(endpoint) => {
+                    if !quiet {
+                        println!("{} {service} is healthy at {endpoint}", self.project);
+                    }
+                    return Ok(endpoint);
+                }
+                Err(e) => e,
+            };
+
+            if Instant::now() + interval > deadline {
+                return Err(anyhow!(
+                    "{} {service} was not healthy after {}s: {err}",
+                    self.project,
+                    self.timeout
+                ));
+            }
+
+            sleep(interval).await;
+        }
+    }
+
+    /// Look up the deployment's endpoint and make one request against it, returning the
+    /// endpoint if it answered.
+    async fn check(&self, client: &ApiClient, http: &reqwest::Client) -> Result<String> {
+        let deployment = describe::fetch(client, &self.project, &self.service).await?;
+
+        let (endpoint, body) = match deployment.config {
+            DescribeDeploymentDeploymentConfig::KatanaConfig(config) => (
+                config.rpc,
+                json!({ "jsonrpc": "2.0", "id": 1, "method": "starknet_chainId", "params": [] }),
+            ),
+            DescribeDeploymentDeploymentConfig::ToriiConfig(config) if self.indexed => (
+                config.graphql,
+                json!({ "query": "{ models { totalCount } }" }),
+            ),
+            DescribeDeploymentDeploymentConfig::ToriiConfig(config) => {
+                (config.graphql, json!({ "query": "{ __typename }" }))
+            }
+        };
+
+        let res = http.post(&endpoint).json(&body).send().await?;
+        let status = res.status();
+        if !status.is_success() {
+            return Err(anyhow!("{endpoint} responded with {status}"));
+        }
+
+        let res: Value = res.json().await?;
+        if let Some(error) = res.get("error").or_else(|| res.get("errors")) {
+            return Err(anyhow!("{endpoint} responded with an error: {error}"));
+        }
+
+        if self.indexed && res["data"]["models"]["totalCount"].as_u64().unwrap_or(0) == 0 {
+            return Err(anyhow!("the world has not been indexed yet"));
+        }
+
+        Ok(endpoint)
+    }
+}
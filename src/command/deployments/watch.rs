@@ -0,0 +1,54 @@
+use std::{collections::HashSet, future::Future, time::Duration};
+
+use anyhow::Result;
+use tokio::time::sleep;
+
+use crate::output;
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Redraw the output of `render` every `interval` until Ctrl-C. Lines that weren't in the
+/// previous frame are shown in bold so changes stand out.
+pub async fn watch<F, Fut>(interval: Duration, mut render: F) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    output::text_only("--watch")?;
+
+    // One listener for the whole watch, so Ctrl-C also stops it in the middle of a refresh.
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    let mut previous: Option<HashSet<String>> = None;
+    loop {
+        let rendered = tokio::select! {
+            rendered = render() => rendered,
+            _ = &mut ctrl_c => return Ok(()),
+        };
+        match rendered {
+            Ok(frame) => {
+                print!("{CLEAR_SCREEN}");
+                for line in frame.lines() {
+                    if previous.as_ref().is_some_and(|p| !p.contains(line)) {
+                        println!("\x1b[1m{line}\x1b[0m");
+                    } else {
+                        println!("{line}");
+                    }
+                }
+                println!(
+                    "\nRefreshing every {}s, Ctrl-C to stop.",
+                    interval.as_secs()
+                );
+                previous = Some(frame.lines().map(String::from).collect());
+            }
+            // Keep the last frame on screen; a dropped request shouldn't end the watch.
+            Err(e) => eprintln!("Warning: refresh failed: {e}"),
+        }
+
+        tokio::select! {
+            _ = sleep(interval) => {}
+            _ = &mut ctrl_c => return Ok(()),
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use std::{
+    env,
+    net::TcpListener,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use clap::Args;
+use reqwest::header::DATE;
+use serde::Serialize;
+
+use crate::{
+    api::ApiClient,
+    browser::{self, Environment},
+    config::{config_dir, Config},
+    constant,
+    credential::{self, Credentials},
+    output,
+};
+
+use super::auth::info::{me::Variables, Me};
+
+/// Clock skew beyond which token expiry checks start to misbehave.
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(60);
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Args)]
+pub struct DoctorArgs {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Ok,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            status: Status::Warn,
+            fix: Some(fix.into()),
+            ..Self::ok(name, detail)
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            status: Status::Fail,
+            ..Self::warn(name, detail, fix)
+        }
+    }
+}
+
+impl DoctorArgs {
+    pub async fn run(&self) -> Result<()> {
+        let mut checks = vec![config(), profile()];
+        #[cfg(unix)]
+        checks.push(permissions());
+        let (api, reachable) = api().await;
+        checks.extend(api);
+        checks.push(credentials(reachable).await);
+        checks.push(browser());
+        checks.push(callback_port());
+
+        if output::is_json() {
+            output::print_json(&checks)?;
+        } else {
+            for check in &checks {
+                let status = match check.status {
+                    Status::Ok => "ok",
+                    Status::Warn => "warn",
+                    Status::Fail => "fail",
+                };
+                println!("[{status:^4}] {}: {}", check.name, check.detail);
+                if let Some(fix) = &check.fix {
+                    println!("       Fix: {fix}");
+                }
+            }
+        }
+
+        match checks.iter().filter(|c| c.status == Status::Fail).count() {
+            0 => Ok(()),
+            1 => Err(anyhow!("1 check failed.")),
+            failed => Err(anyhow!("{failed} checks failed.")),
+        }
+    }
+}
+
+fn config() -> Check {
+    let path = config_dir().map(|dir| dir.join("config.toml"));
+    let path = path
+        .as_ref()
+        .map_or("config.toml".into(), |p| p.display().to_string());
+    match Config::load() {
+        Ok(_) => Check::ok("Config", format!("{path} is valid")),
+        Err(e) => Check::fail("Config", e.to_string(), format!("Fix or remove {path}.")),
+    }
+}
+
+fn profile() -> Check {
+    match credential::active_profile() {
+        Ok(profile) => Check::ok("Profile", format!("using `{profile}`")),
+        Err(e) => Check::fail(
+            "Profile",
+            e.to_string(),
+            "Pick a valid profile with `slot auth switch`, or fix SLOT_PROFILE.",
+        ),
+    }
+}
+
+/// Credentials in a file others can read are as good as leaked.
+#[cfg(unix)]
+fn permissions() -> Check {
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    let path = match credential::active_profile().and_then(|p| Credentials::path(&p)) {
+        Ok(path) => path,
+        Err(e) => return Check::fail("Permissions", e.to_string(), "Fix the profile first."),
+    };
+
+    match fs::metadata(&path) {
+        Ok(metadata) if metadata.permissions().mode() & 0o077 != 0 => Check::fail(
+            "Permissions",
+            format!(
+                "{} is readable by other users ({:o})",
+                path.display(),
+                metadata.permissions().mode() & 0o777
+            ),
+            format!("Run `chmod 600 {}`.", path.display()),
+        ),
+        Ok(_) => Check::ok("Permissions", format!("{} is private", path.display())),
+        Err(_) => Check::ok("Permissions", "no credentials file on disk"),
+    }
+}
+
+/// Reach the API, and compare its clock with ours while at it.
+async fn api() -> (Vec<Check>, bool) {
+    let url = constant::api_url();
+    let client = match reqwest::Client::builder()
+        .timeout(REACHABILITY_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            return (
+                vec![Check::fail("API", e.to_string(), "Report this bug.")],
+                false,
+            )
+        }
+    };
+
+    let start = Instant::now();
+    // Any HTTP response will do, the root may well be a 404.
+    let response = match client.get(&url).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            let check = Check::fail(
+                "API",
+                format!("{url} is unreachable: {e}"),
+                "Check your connection and proxy, or the `api_url` setting and SLOT_API_URL.",
+            );
+            return (vec![check], false);
+        }
+    };
+    let api = Check::ok(
+        "API",
+        format!("{url} answered in {}ms", start.elapsed().as_millis()),
+    );
+
+    let server_time = response
+        .headers()
+        .get(DATE)
+        .and_then(|date| date.to_str().ok())
+        .and_then(|date| DateTime::parse_from_rfc2822(date).ok());
+    let clock = match server_time {
+        Some(server_time) => {
+            let skew = (Utc::now() - server_time.with_timezone(&Utc))
+                .num_seconds()
+                .unsigned_abs();
+            if skew > MAX_CLOCK_SKEW.as_secs() {
+                Check::fail(
+                    "Clock",
+                    format!("{skew}s off from the API"),
+                    "Sync the system clock, e.g. by enabling NTP.",
+                )
+            } else {
+                Check::ok("Clock", format!("{skew}s off from the API"))
+            }
+        }
+        None => Check::warn(
+            "Clock",
+            "the API didn't report its time",
+            "Make sure the system clock is synced.",
+        ),
+    };
+
+    (vec![api, clock], true)
+}
+
+async fn credentials(reachable: bool) -> Check {
+    let source = if env::var("SLOT_AUTH_TOKEN").is_ok_and(|t| !t.is_empty()) {
+        "SLOT_AUTH_TOKEN"
+    } else {
+        "stored credentials"
+    };
+    if let Err(e) = Credentials::load() {
+        return Check::fail("Credentials", e.to_string(), "Run `slot auth login`.");
+    }
+    if !reachable {
+        return Check::warn(
+            "Credentials",
+            format!("found {source}, but couldn't validate them"),
+            "Fix the API check first.",
+        );
+    }
+
+    match ApiClient::new().query::<Me>(Variables {}).await {
+        Ok(data) => match data.me {
+            Some(me) => Check::ok(
+                "Credentials",
+                format!(
+                    "{source} valid for {}",
+                    me.name.as_deref().unwrap_or(&me.id)
+                ),
+            ),
+            None => Check::fail(
+                "Credentials",
+                format!("{source} aren't tied to an account"),
+                "Run `slot auth login`.",
+            ),
+        },
+        Err(e) => Check::fail(
+            "Credentials",
+            format!("{source} were rejected: {e}"),
+            "Run `slot auth login`, or replace SLOT_AUTH_TOKEN.",
+        ),
+    }
+}
+
+/// `slot auth login` falls back to printing the URL, so a missing browser isn't fatal.
+fn browser() -> Check {
+    if let Some(browser) = env::var("BROWSER").ok().filter(|b| !b.is_empty()) {
+        return Check::ok("Browser", format!("login opens `{browser}`"));
+    }
+
+    match Environment::detect() {
+        Environment::Desktop => Check::ok("Browser", "login opens the system browser"),
+        Environment::Wsl if browser::on_path("wslview") => {
+            Check::ok("Browser", "login opens the Windows browser with `wslview`")
+        }
+        Environment::Wsl => Check::warn(
+            "Browser",
+            "running in WSL without `wslview`, login will print the URL instead",
+            "Install wslu, e.g. `sudo apt install wslu`.",
+        ),
+        Environment::Ssh => Check::warn(
+            "Browser",
+            "running over SSH, login will print the URL instead",
+            "Forward the callback port with `ssh -L`, or use `slot auth login --manual`.",
+        ),
+        Environment::Headless => Check::warn(
+            "Browser",
+            "no display, login will print the URL instead",
+            "Use `slot auth login --manual`, or set BROWSER.",
+        ),
+    }
+}
+
+fn callback_port() -> Check {
+    let host = env::var("SLOT_CALLBACK_HOST").unwrap_or_else(|_| "localhost".to_string());
+    let port = env::var("SLOT_CALLBACK_PORT").unwrap_or_else(|_| "0".to_string());
+    let Ok(port) = port.parse::<u16>() else {
+        return Check::fail(
+            "Callback port",
+            format!("SLOT_CALLBACK_PORT `{port}` isn't a port"),
+            "Set SLOT_CALLBACK_PORT to a number between 0 and 65535.",
+        );
+    };
+
+    match TcpListener::bind((host.as_str(), port)) {
+        Ok(listener) => {
+            let addr = listener.local_addr().map_or(host, |a| a.to_string());
+            Check::ok("Callback port", format!("login can listen on {addr}"))
+        }
+        Err(e) => Check::fail(
+            "Callback port",
+            format!("can't listen on {host}:{port}: {e}"),
+            "Free the port, or pick another with `--callback-port` or SLOT_CALLBACK_PORT.",
+        ),
+    }
+}
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use clap::Args;
+use graphql_client::GraphQLQuery;
+
+use serde_json::json;
+
+use crate::{api::ApiClient, output};
+
+use self::list_teams::Variables;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.json",
+    query_path = "src/command/teams/list.graphql",
+    response_derives = "Debug"
+)]
+pub struct ListTeams;
+
+#[derive(Debug, Args)]
+pub struct ListArgs {}
+
+impl ListArgs {
+    pub async fn run(&self) -> Result<()> {
+        let client = ApiClient::new();
+        let data = client.query::<ListTeams>(Variables {}).await?;
+
+        let teams = data
+            .me
+            .and_then(|me| me.teams.edges)
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .filter_map(|edge| edge.node);
+
+        if output::is_json() {
+            let teams = teams
+                .map(|team| {
+                    json!({
+                        "id": team.id,
+                        "name": team.name,
+                        "description": team.description,
+                        "members": team.membership.total_count,
+                        "deployments": team.deployments.total_count,
+                    })
+                })
+                .collect::<Vec<_>>();
+            return output::print_json(&teams);
+        }
+
+        for team in teams {
+            println!("Team: {}", team.name);
+            if let Some(description) = team.description.filter(|d| !d.is_empty()) {
+                println!("Description: {description}");
+            }
+            println!("Members: {}", team.membership.total_count);
+            println!("Deployments: {}", team.deployments.total_count);
+            println!("---");
+        }
+
+        Ok(())
+    }
+}
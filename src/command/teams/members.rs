@@ -0,0 +1,71 @@
+use anyhow::{anyhow, Result};
+use clap::Args;
+use graphql_client::GraphQLQuery;
+
+use serde_json::json;
+
+use crate::{api::ApiClient, output};
+
+use self::team_members::Variables;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.json",
+    query_path = "src/command/teams/members.graphql",
+    response_derives = "Debug"
+)]
+pub struct TeamMembers;
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Members options")]
+pub struct MembersArgs {
+    #[arg(help = "The name of the team.")]
+    pub team: String,
+}
+
+impl MembersArgs {
+    pub async fn run(&self) -> Result<()> {
+        let client = ApiClient::new();
+        let data = client.query::<TeamMembers>(Variables {}).await?;
+
+        let team = data
+            .me
+            .and_then(|me| me.teams.edges)
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .filter_map(|edge| edge.node)
+            .find(|team| team.name == self.team)
+            .ok_or_else(|| anyhow!("You are not a member of team {}.", self.team))?;
+
+        let members = team
+            .membership
+            .edges
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|edge| edge.node);
+
+        if output::is_json() {
+            let members = members
+                .map(|membership| {
+                    json!({
+                        "id": membership.account.id,
+                        "name": membership.account.name,
+                        "role": format!("{:?}", membership.role),
+                    })
+                })
+                .collect::<Vec<_>>();
+            return output::print_json(&members);
+        }
+
+        for membership in members {
+            let account = membership.account;
+            println!("Account: {}", account.name.unwrap_or(account.id));
+            println!("Role: {:?}", membership.role);
+            println!("---");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use anyhow::Result;
+use clap::Subcommand;
+
+use self::{list::ListArgs, members::MembersArgs};
+
+mod list;
+mod members;
+
+#[derive(Subcommand, Debug)]
+pub enum Teams {
+    #[command(about = "List the teams you belong to.", aliases = ["ls"])]
+    List(ListArgs),
+    #[command(about = "List the members of a team.")]
+    Members(MembersArgs),
+}
+
+impl Teams {
+    pub async fn run(&self) -> Result<()> {
+        match &self {
+            Teams::List(args) => args.run().await,
+            Teams::Members(args) => args.run().await,
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use anyhow::{anyhow, Result};
+use clap::{Args, ValueEnum};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::{constant::REPO, output};
+
+use super::version::parse_version;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Channel {
+    Stable,
+    Nightly,
+}
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Update options")]
+pub struct UpdateArgs {
+    #[arg(long, default_value = "stable")]
+    #[arg(value_name = "channel")]
+    #[arg(help = "Release channel to update from.")]
+    pub channel: Channel,
+
+    #[arg(long)]
+    #[arg(help = "Install even if the release doesn't publish a checksum for the download.")]
+    pub no_verify: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct Release {
+    pub(super) tag_name: String,
+    assets: Vec<Asset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+    /// `sha256:<hex>`, published by GitHub for each uploaded asset.
+    digest: Option<String>,
+}
+
+impl UpdateArgs {
+    pub async fn run(&self) -> Result<()> {
+        output::text_only("slot update")?;
+        let client = github_client()?;
+
+        let (release, version) = match self.channel {
+            Channel::Stable => {
+                let release = fetch_release(&client, "latest").await?;
+                let version = release.tag_name.clone();
+                // Never downgrade, e.g. a build from main that's ahead of the last release.
+                let current = env!("CARGO_PKG_VERSION");
+                if parse_version(version.trim_start_matches('v')) <= parse_version(current) {
+                    println!("slot {current} is up to date, the latest release is {version}.");
+                    return Ok(());
+                }
+                (release, version)
+            }
+            // Nightly assets are named after the channel rather than the tag.
+            Channel::Nightly => (
+                fetch_release(&client, "tags/nightly").await?,
+                String::from("nightly"),
+            ),
+        };
+
+        let name = asset_name(&version)?;
+        let asset = release
+            .assets
+            .iter()
+            .find(|asset| asset.name == name)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Release {} has no build for this platform ({name}).",
+                    release.tag_name
+                )
+            })?;
+
+        println!("Downloading {}...", asset.name);
+        let archive = client
+            .get(&asset.browser_download_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        match asset
+            .digest
+            .as_deref()
+            .and_then(|d| d.strip_prefix("sha256:"))
+        {
+            Some(expected) => {
+                let actual = format!("{:x}", Sha256::digest(&archive));
+                if actual != expected {
+                    return Err(anyhow!(
+                        "Checksum mismatch for {}: expected {expected}, got {actual}.",
+                        asset.name
+                    ));
+                }
+            }
+            None if self.no_verify => {
+                eprintln!(
+                    "Warning: {} has no published checksum, installing unverified.",
+                    asset.name
+                );
+            }
+            None => {
+                return Err(anyhow!(
+                    "{} has no published checksum. Rerun with `--no-verify` to install it anyway.",
+                    asset.name
+                ));
+            }
+        }
+
+        let dir = env::temp_dir().join(format!("slot-update-{}", rand::random::<u32>()));
+        fs::create_dir_all(&dir)?;
+        let result = install(&dir, &asset.name, &archive);
+        let _ = fs::remove_dir_all(&dir);
+        let path = result?;
+
+        println!("Updated {} to {}.", path.display(), release.tag_name);
+        Ok(())
+    }
+}
+
+/// GitHub's API rejects requests without a User-Agent.
+pub(super) fn github_client() -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder()
+        .user_agent(concat!("slot/", env!("CARGO_PKG_VERSION")))
+        .build()?)
+}
+
+pub(super) async fn fetch_release(client: &reqwest::Client, which: &str) -> Result<Release> {
+    let url = format!("https://api.github.com/repos/{REPO}/releases/{which}");
+    let release = client
+        .get(url)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await?
+        .error_for_status()
+        .map_err(|e| anyhow!("Failed to look up the release: {e}"))?
+        .json()
+        .await?;
+    Ok(release)
+}
+
+/// The archive name the release workflow uploads for this platform, e.g.
+/// `slot_v0.5.7_linux_amd64.tar.gz`.
+fn asset_name(version: &str) -> Result<String> {
+    let (platform, ext) = match env::consts::OS {
+        "linux" => ("linux", "tar.gz"),
+        "macos" => ("darwin", "tar.gz"),
+        "windows" => ("win32", "zip"),
+        os => return Err(anyhow!("No prebuilt slot binaries for {os}.")),
+    };
+    let arch = match env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        arch => return Err(anyhow!("No prebuilt slot binaries for {arch}.")),
+    };
+
+    Ok(format!("slot_{version}_{platform}_{arch}.{ext}"))
+}
+
+/// Unpack the archive in `dir` and swap the new binary in for the running one.
+fn install(dir: &Path, name: &str, archive: &[u8]) -> Result<PathBuf> {
+    let archive_path = dir.join(name);
+    fs::write(&archive_path, archive)?;
+
+    // tar ships with Linux, macOS and Windows 10+, where it also reads zip files.
+    let status = process::Command::new("tar")
+        .arg("-xf")
+        .arg(&archive_path)
+        .arg("-C")
+        .arg(dir)
+        .status()
+        .map_err(|e| anyhow!("Failed to run tar: {e}"))?;
+    if !status.success() {
+        return Err(anyhow!(
+            "Failed to extract {name}: tar exited with {status}."
+        ));
+    }
+
+    let binary = dir.join(format!("slot{}", env::consts::EXE_SUFFIX));
+    let current = env::current_exe()?;
+    let staged = current.with_extension("new");
+    fs::copy(&binary, &staged)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))?;
+    }
+
+    // Windows won't overwrite a running executable, but it will rename one.
+    #[cfg(windows)]
+    let old = {
+        let old = current.with_extension("old");
+        let _ = fs::remove_file(&old);
+        fs::rename(&current, &old)?;
+        Some(old)
+    };
+    #[cfg(not(windows))]
+    let old: Option<PathBuf> = None;
+
+    if let Err(e) = fs::rename(&staged, &current) {
+        // Put the old binary back rather than leave no `slot` at all.
+        if let Some(old) = &old {
+            let _ = fs::rename(old, &current);
+        }
+        let _ = fs::remove_file(&staged);
+        return Err(anyhow!("Failed to replace {}: {e}", current.display()));
+    }
+    Ok(current)
+}
@@ -0,0 +1,57 @@
+use anyhow::Result;
+use clap::Args;
+use serde_json::json;
+
+use crate::output;
+
+use super::update::{fetch_release, github_client};
+
+#[derive(Debug, Args)]
+pub struct VersionArgs {
+    #[arg(long)]
+    #[arg(help = "Check whether a newer release of the CLI is available.")]
+    pub check: bool,
+}
+
+impl VersionArgs {
+    pub async fn run(&self) -> Result<()> {
+        let current = env!("CARGO_PKG_VERSION");
+        if !self.check {
+            if output::is_json() {
+                return output::print_json(&json!({ "version": current }));
+            }
+            println!("slot {current}");
+            return Ok(());
+        }
+
+        let latest = fetch_release(&github_client()?, "latest").await?.tag_name;
+        let latest = latest.trim_start_matches('v');
+        let newer = parse_version(latest) > parse_version(current);
+        if output::is_json() {
+            return output::print_json(&json!({
+                "version": current,
+                "latest": latest,
+                "update_available": newer,
+            }));
+        }
+
+        println!("slot {current}");
+        if newer {
+            eprintln!(
+                "Warning: slot {latest} is available. Newer deployment options may not be usable from this version, run `slot update` to upgrade."
+            );
+        } else {
+            println!("This is the latest release.");
+        }
+
+        Ok(())
+    }
+}
+
+/// `0.5.7` as `[0, 5, 7]`, so versions compare numerically component by component.
+pub(super) fn parse_version(version: &str) -> Vec<u64> {
+    version
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use std::{env, fs, io, path::PathBuf, sync::OnceLock};
+
+use crate::{
+    error::Error,
+    storage::{FileStorage, Storage},
+};
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// User defaults read from `slot/config.toml`. Command-line flags and environment variables
+/// take precedence over anything set here.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub team: Option<String>,
+    pub katana_version: Option<String>,
+    pub torii_version: Option<String>,
+    pub api_url: Option<String>,
+    pub keychain_url: Option<String>,
+    pub output: Option<String>,
+    pub state_dir: Option<String>,
+}
+
+impl Config {
+    pub const KEYS: &'static [&'static str] = &[
+        "team",
+        "katana_version",
+        "torii_version",
+        "api_url",
+        "keychain_url",
+        "output",
+        "state_dir",
+    ];
+
+    /// Load the config file once per process. Later calls return the cached value.
+    pub fn init() -> Result<&'static Config, Error> {
+        if let Some(config) = CONFIG.get() {
+            return Ok(config);
+        }
+
+        let config = Self::load()?;
+        Ok(CONFIG.get_or_init(|| config))
+    }
+
+    /// The startup config, or defaults if it hasn't been loaded.
+    pub fn get() -> &'static Config {
+        CONFIG.get_or_init(|| Self::load().unwrap_or_default())
+    }
+
+    /// Defaults when there is no config file, or no config directory to look for one in.
+    pub fn load() -> Result<Self, Error> {
+        let path = match Self::path() {
+            Ok(path) => path,
+            Err(Error::ConfigDirNotFound) => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+
+        match fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents).map_err(Error::MalformedConfig),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(Error::Io(e)),
+        }
+    }
+
+    pub fn write(&self) -> Result<(), Error> {
+        let serialized =
+            toml::to_string(self).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        FileStorage::new(config_dir()?).write("config.toml", serialized.as_bytes(), false)?;
+        Ok(())
+    }
+
+    pub fn value(&self, key: &str) -> Result<Option<&str>, Error> {
+        let value = match key {
+            "team" => &self.team,
+            "katana_version" => &self.katana_version,
+            "torii_version" => &self.torii_version,
+            "api_url" => &self.api_url,
+            "keychain_url" => &self.keychain_url,
+            "output" => &self.output,
+            "state_dir" => &self.state_dir,
+            _ => return Err(Error::UnknownConfigKey(key.to_string())),
+        };
+
+        Ok(value.as_deref())
+    }
+
+    /// Set `key` to `value`, or clear it when `value` is `None`.
+    pub fn set(&mut self, key: &str, value: Option<String>) -> Result<(), Error> {
+        let slot = match key {
+            "team" => &mut self.team,
+            "katana_version" => &mut self.katana_version,
+            "torii_version" => &mut self.torii_version,
+            "api_url" => &mut self.api_url,
+            "keychain_url" => &mut self.keychain_url,
+            "output" => {
+                if let Some(output) = value.as_deref().filter(|o| !["text", "json"].contains(o)) {
+                    return Err(Error::InvalidConfigValue(
+                        key.to_string(),
+                        output.to_string(),
+                    ));
+                }
+                &mut self.output
+            }
+            "state_dir" => &mut self.state_dir,
+            _ => return Err(Error::UnknownConfigKey(key.to_string())),
+        };
+
+        *slot = value;
+        Ok(())
+    }
+
+    fn path() -> Result<PathBuf, Error> {
+        Ok(config_dir()?.join("config.toml"))
+    }
+}
+
+pub fn config_dir() -> Result<PathBuf, Error> {
+    let mut path = dirs::config_local_dir().ok_or(Error::ConfigDirNotFound)?;
+    path.push("slot");
+    Ok(path)
+}
+
+/// Where credentials and the active profile are kept: `SLOT_STATE_DIR`, then `state_dir` in
+/// the config, then the platform state directory (`$XDG_STATE_HOME/slot` on Linux).
+pub fn state_dir() -> Result<PathBuf, Error> {
+    if let Some(dir) = env::var_os("SLOT_STATE_DIR").filter(|dir| !dir.is_empty()) {
+        return Ok(dir.into());
+    }
+    if let Some(dir) = Config::get()
+        .state_dir
+        .as_deref()
+        .filter(|dir| !dir.is_empty())
+    {
+        return Ok(dir.into());
+    }
+
+    // Older versions kept state next to the config; moving it would log everyone out.
+    if let Ok(legacy) = config_dir() {
+        if LEGACY_STATE.iter().any(|name| legacy.join(name).exists()) {
+            return Ok(legacy);
+        }
+    }
+
+    let mut path = dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .ok_or(Error::StateDirNotFound)?;
+    path.push("slot");
+    Ok(path)
+}
+
+const LEGACY_STATE: [&str; 3] = ["credentials.json", "profile", "profiles"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_and_clear_every_key() {
+        let mut config = Config::default();
+        for key in Config::KEYS {
+            let value = if *key == "output" { "json" } else { "value" };
+            config.set(key, Some(value.to_string())).unwrap();
+            assert_eq!(config.value(key).unwrap(), Some(value), "{key}");
+
+            config.set(key, None).unwrap();
+            assert_eq!(config.value(key).unwrap(), None, "{key}");
+        }
+    }
+
+    #[test]
+    fn output_must_be_text_or_json() {
+        let mut config = Config::default();
+        config.set("output", Some("text".to_string())).unwrap();
+
+        let err = config.set("output", Some("yaml".to_string())).unwrap_err();
+        assert!(
+            matches!(err, Error::InvalidConfigValue(key, value) if key == "output" && value == "yaml")
+        );
+        assert_eq!(config.output.as_deref(), Some("text"));
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let mut config = Config::default();
+
+        let err = config.set("colour", Some("auto".to_string())).unwrap_err();
+        assert!(matches!(err, Error::UnknownConfigKey(key) if key == "colour"));
+        assert!(matches!(
+            config.value("colour"),
+            Err(Error::UnknownConfigKey(_))
+        ));
+    }
+}
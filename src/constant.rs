@@ -1,3 +1,32 @@
-// TODO: env instead?
-pub const CARTRIDGE_API_URL: &str = "https://api.cartridge.gg/";
-pub const CARTRIDGE_KEYCHAIN_URL: &str = "https://x.cartridge.gg/";
+use std::env;
+
+use crate::config::Config;
+
+pub const CARTRIDGE_API_URL: &str = "https://api.cartridge.gg";
+pub const CARTRIDGE_KEYCHAIN_URL: &str = "https://x.cartridge.gg";
+/// The GitHub repository releases and docs are published from.
+pub const REPO: &str = "cartridge-gg/slot";
+
+/// Cartridge API base URL, overridable with `SLOT_API_URL` or `api_url` in the config.
+pub fn api_url() -> String {
+    resolve("SLOT_API_URL", &Config::get().api_url, CARTRIDGE_API_URL)
+}
+
+/// Keychain base URL, overridable with `SLOT_KEYCHAIN_URL` or `keychain_url` in the config.
+pub fn keychain_url() -> String {
+    resolve(
+        "SLOT_KEYCHAIN_URL",
+        &Config::get().keychain_url,
+        CARTRIDGE_KEYCHAIN_URL,
+    )
+}
+
+fn resolve(key: &str, configured: &Option<String>, default: &str) -> String {
+    env::var(key)
+        .ok()
+        .or_else(|| configured.clone())
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| default.to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
@@ -1,30 +1,416 @@
 use serde::{Deserialize, Serialize};
-use std::fs::{self, OpenOptions};
-use std::io::{self, Read, Write};
+use std::io;
+use std::{
+    env,
+    path::PathBuf,
+    sync::{OnceLock, RwLock},
+};
 
-#[derive(Serialize, Deserialize)]
+use crate::{
+    error::Error,
+    storage::{FileStorage, Storage},
+};
+
+const KEYRING_SERVICE: &str = "slot";
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// What [`Credentials::load`] last returned, so a command making many requests reads the
+/// keychain or disk once instead of once per request.
+static CACHE: OnceLock<RwLock<Option<Credentials>>> = OnceLock::new();
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Credentials {
     pub access_token: String,
     pub token_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
 }
 
 impl Credentials {
-    pub fn load() -> io::Result<Self> {
-        let mut path = dirs::config_local_dir().unwrap();
-        path.push("slot/credentials.json");
-        let mut file = OpenOptions::new().read(true).open(&path)?;
-        let mut contents = String::new();
-        file.read_to_string(&mut contents)?;
-        let credentials: Credentials = serde_json::from_str(&contents)?;
+    /// Load credentials from `SLOT_AUTH_TOKEN`, else those of the active profile. They're
+    /// cached for the rest of the process, until [`Credentials::invalidate`] is called.
+    pub fn load() -> Result<Self, Error> {
+        if let Some(credentials) = cache().read().unwrap().as_ref() {
+            return Ok(credentials.clone());
+        }
+
+        let credentials = Self::load_uncached()?;
+        *cache().write().unwrap() = Some(credentials.clone());
         Ok(credentials)
     }
 
-    pub fn write(&self) -> io::Result<()> {
-        let mut path = dirs::config_local_dir().unwrap();
-        path.push("slot/credentials.json");
-        fs::create_dir_all(path.parent().unwrap())?;
-        let mut file = OpenOptions::new().write(true).create(true).open(&path)?;
-        let serialized = serde_json::to_string(self)?;
-        file.write_all(serialized.as_bytes())
+    /// Forget the cached credentials, so the next [`Credentials::load`] reads them again.
+    /// Writing or deleting credentials and switching profiles do this already.
+    pub fn invalidate() {
+        *cache().write().unwrap() = None;
+    }
+
+    fn load_uncached() -> Result<Self, Error> {
+        if let Some(token) = env::var("SLOT_AUTH_TOKEN").ok().filter(|t| !t.is_empty()) {
+            return Ok(Self::from_token(token));
+        }
+
+        Self::load_profile(&active_profile()?)
+    }
+
+    pub fn from_token(access_token: String) -> Self {
+        Self {
+            access_token,
+            token_type: "Bearer".to_string(),
+            refresh_token: None,
+        }
+    }
+
+    /// Load credentials from the OS keychain, falling back to the credentials file.
+    pub fn load_profile(profile: &str) -> Result<Self, Error> {
+        if let Some(contents) = keyring(profile).and_then(|entry| entry.get_password().ok()) {
+            return serde_json::from_str(&contents).map_err(Error::MalformedCredentials);
+        }
+
+        Self::load_file(profile)
+    }
+
+    /// Store credentials in the OS keychain, or in the credentials file when no keychain is
+    /// available (e.g. headless Linux without a Secret Service provider).
+    pub fn write(&self) -> Result<(), Error> {
+        // Hold the cache while storing, so no request caches the credentials being replaced.
+        let mut cached = cache().write().unwrap();
+        *cached = None;
+        self.store(&active_profile()?)?;
+        if env::var("SLOT_AUTH_TOKEN").unwrap_or_default().is_empty() {
+            *cached = Some(self.clone());
+        }
+        Ok(())
+    }
+
+    /// Store credentials under `profile`, which doesn't have to be the active one.
+    pub fn write_profile(&self, profile: &str) -> Result<(), Error> {
+        validate_profile(profile)?;
+        if profile == active_profile()? {
+            return self.write();
+        }
+        self.store(profile)
+    }
+
+    fn store(&self, profile: &str) -> Result<(), Error> {
+        let serialized = serde_json::to_string(self).map_err(io::Error::from)?;
+
+        if let Some(entry) = keyring(profile) {
+            if entry.set_password(&serialized).is_ok() {
+                return Ok(());
+            }
+        }
+
+        self.write_file(profile, &serialized)
+    }
+
+    /// Remove credentials of the active profile from both the OS keychain and disk.
+    pub fn delete() -> Result<(), Error> {
+        Self::invalidate();
+        let profile = active_profile()?;
+
+        if let Some(entry) = keyring(&profile) {
+            // Nothing stored, or no keychain on this machine: the file is all there is.
+            match entry.delete_password() {
+                Ok(())
+                | Err(keyring::Error::NoEntry)
+                | Err(keyring::Error::PlatformFailure(_))
+                | Err(keyring::Error::NoStorageAccess(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Self::remove_from(&FileStorage::state()?, &Self::key(&profile)?)
+    }
+
+    /// Move credentials of the active profile from the credentials file into the OS keychain.
+    pub fn migrate_to_keyring() -> Result<(), Error> {
+        let profile = active_profile()?;
+        let credentials = Self::load_file(&profile)?;
+        let serialized = serde_json::to_string(&credentials).map_err(io::Error::from)?;
+
+        keyring::Entry::new(KEYRING_SERVICE, &keyring_user(&profile))?.set_password(&serialized)?;
+        Self::remove_from(&FileStorage::state()?, &Self::key(&profile)?)?;
+
+        Ok(())
+    }
+
+    fn load_file(profile: &str) -> Result<Self, Error> {
+        let storage = FileStorage::state()?;
+        let key = Self::key(profile)?;
+        #[cfg(unix)]
+        warn_if_exposed(&storage.path(&key));
+        Self::load_from(&storage, &key)
+    }
+
+    /// Falls back to the backup, and restores it, when the file is corrupt.
+    fn load_from(storage: &impl Storage, key: &str) -> Result<Self, Error> {
+        let backup = format!("{key}.bak");
+        match Self::read(storage, key) {
+            Err(Error::MalformedCredentials(e)) => {
+                let Ok(credentials) = Self::read(storage, &backup) else {
+                    return Err(Error::MalformedCredentials(e));
+                };
+
+                eprintln!(
+                    "Warning: '{key}' is corrupt ({e}). Restored the previous credentials from '{backup}'."
+                );
+                if let Ok(serialized) = serde_json::to_string(&credentials) {
+                    let _ = storage.write(key, serialized.as_bytes(), true);
+                }
+                Ok(credentials)
+            }
+            result => result,
+        }
+    }
+
+    fn read(storage: &impl Storage, key: &str) -> Result<Self, Error> {
+        let contents = storage.read(key)?.ok_or(Error::CredentialsNotFound)?;
+        serde_json::from_slice(&contents).map_err(Error::MalformedCredentials)
+    }
+
+    fn write_file(&self, profile: &str, serialized: &str) -> Result<(), Error> {
+        self.write_to(&FileStorage::state()?, &Self::key(profile)?, serialized)
+    }
+
+    /// Keeps the last good credentials as a backup to recover from.
+    fn write_to(&self, storage: &impl Storage, key: &str, serialized: &str) -> Result<(), Error> {
+        if let Ok(previous) = Self::read(storage, key) {
+            let previous = serde_json::to_string(&previous).map_err(io::Error::from)?;
+            storage.write(&format!("{key}.bak"), previous.as_bytes(), true)?;
+        }
+        storage.write(key, serialized.as_bytes(), true)?;
+        Ok(())
+    }
+
+    fn remove_from(storage: &impl Storage, key: &str) -> Result<(), Error> {
+        storage.remove(&format!("{key}.bak"))?;
+        storage.remove(key)?;
+        Ok(())
+    }
+
+    /// The default profile keeps the original `credentials.json` location in the state directory.
+    fn key(profile: &str) -> Result<String, Error> {
+        validate_profile(profile)?;
+        if profile == DEFAULT_PROFILE {
+            Ok("credentials.json".to_string())
+        } else {
+            Ok(format!("profiles/{profile}/credentials.json"))
+        }
+    }
+
+    pub(crate) fn path(profile: &str) -> Result<PathBuf, Error> {
+        Ok(FileStorage::state()?.path(&Self::key(profile)?))
+    }
+}
+
+/// The profile used by every command: `SLOT_PROFILE` if set, else the one picked with
+/// `slot auth switch`, else [`DEFAULT_PROFILE`].
+pub fn active_profile() -> Result<String, Error> {
+    if let Ok(profile) = env::var("SLOT_PROFILE") {
+        validate_profile(&profile)?;
+        return Ok(profile);
+    }
+
+    stored_profile(&FileStorage::state()?)
+}
+
+fn stored_profile(storage: &impl Storage) -> Result<String, Error> {
+    let profile = storage.read("profile")?.unwrap_or_default();
+    let profile = String::from_utf8_lossy(&profile).trim().to_string();
+    if profile.is_empty() {
+        Ok(DEFAULT_PROFILE.to_string())
+    } else {
+        Ok(profile)
+    }
+}
+
+pub fn set_active_profile(profile: &str) -> Result<(), Error> {
+    validate_profile(profile)?;
+    Credentials::invalidate();
+    FileStorage::state()?.write("profile", profile.as_bytes(), false)?;
+    Ok(())
+}
+
+fn cache() -> &'static RwLock<Option<Credentials>> {
+    CACHE.get_or_init(Default::default)
+}
+
+pub(crate) fn validate_profile(profile: &str) -> Result<(), Error> {
+    let valid = !profile.is_empty()
+        && profile
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidProfile(profile.to_string()))
+    }
+}
+
+/// Like ssh with private keys, flag credentials that other users on the machine can read.
+#[cfg(unix)]
+fn warn_if_exposed(path: &std::path::Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Ok(metadata) = std::fs::metadata(path) {
+        let mode = metadata.permissions().mode();
+        if mode & 0o077 != 0 {
+            eprintln!(
+                "Warning: permissions {:o} for '{}' are too open. Run `chmod 600 {}`.",
+                mode & 0o777,
+                path.display(),
+                path.display()
+            );
+        }
+    }
+}
+
+fn keyring(profile: &str) -> Option<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, &keyring_user(profile)).ok()
+}
+
+fn keyring_user(profile: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        "credentials".to_string()
+    } else {
+        format!("credentials:{profile}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    const KEY: &str = "credentials.json";
+
+    fn write(storage: &MemoryStorage, token: &str) {
+        let credentials = Credentials::from_token(token.to_string());
+        let serialized = serde_json::to_string(&credentials).unwrap();
+        credentials.write_to(storage, KEY, &serialized).unwrap();
+    }
+
+    fn token(storage: &MemoryStorage) -> String {
+        Credentials::load_from(storage, KEY).unwrap().access_token
+    }
+
+    #[test]
+    fn written_credentials_load_back_as_private_files() {
+        let storage = MemoryStorage::default();
+
+        write(&storage, "one");
+
+        assert_eq!(token(&storage), "one");
+        assert!(storage.is_private(KEY));
+    }
+
+    #[test]
+    fn missing_credentials_are_not_found() {
+        let storage = MemoryStorage::default();
+
+        let result = Credentials::load_from(&storage, KEY);
+
+        assert!(matches!(result, Err(Error::CredentialsNotFound)));
+    }
+
+    #[test]
+    fn overwriting_keeps_the_previous_credentials_as_a_backup() {
+        let storage = MemoryStorage::default();
+
+        write(&storage, "one");
+        write(&storage, "two");
+
+        assert_eq!(token(&storage), "two");
+        let backup = Credentials::read(&storage, "credentials.json.bak").unwrap();
+        assert_eq!(backup.access_token, "one");
+        assert!(storage.is_private("credentials.json.bak"));
+    }
+
+    #[test]
+    fn corrupt_credentials_are_restored_from_the_backup() {
+        let storage = MemoryStorage::default();
+        write(&storage, "one");
+        write(&storage, "two");
+
+        storage.write(KEY, b"{\"access_tok", true).unwrap();
+
+        assert_eq!(token(&storage), "one");
+        assert_eq!(
+            Credentials::read(&storage, KEY).unwrap().access_token,
+            "one"
+        );
+    }
+
+    #[test]
+    fn corrupt_credentials_without_a_backup_are_malformed() {
+        let storage = MemoryStorage::default();
+        storage.write(KEY, b"{\"access_tok", true).unwrap();
+
+        let result = Credentials::load_from(&storage, KEY);
+
+        assert!(matches!(result, Err(Error::MalformedCredentials(_))));
+    }
+
+    #[test]
+    fn a_corrupt_file_never_replaces_a_good_backup() {
+        let storage = MemoryStorage::default();
+        write(&storage, "one");
+        write(&storage, "two");
+        storage.write(KEY, b"not json", true).unwrap();
+
+        // Written over a corrupt file, e.g. by a login after the restore failed.
+        let credentials = Credentials::from_token("three".to_string());
+        let serialized = serde_json::to_string(&credentials).unwrap();
+        credentials.write_to(&storage, KEY, &serialized).unwrap();
+
+        let backup = Credentials::read(&storage, "credentials.json.bak").unwrap();
+        assert_eq!(backup.access_token, "one");
+    }
+
+    #[test]
+    fn removing_credentials_removes_the_backup_too() {
+        let storage = MemoryStorage::default();
+        write(&storage, "one");
+        write(&storage, "two");
+
+        Credentials::remove_from(&storage, KEY).unwrap();
+
+        assert!(storage.keys().is_empty());
+    }
+
+    #[test]
+    fn profiles_other_than_the_default_are_nested() {
+        assert_eq!(
+            Credentials::key(DEFAULT_PROFILE).unwrap(),
+            "credentials.json"
+        );
+        assert_eq!(
+            Credentials::key("work").unwrap(),
+            "profiles/work/credentials.json"
+        );
+    }
+
+    #[test]
+    fn profile_names_cannot_escape_the_state_directory() {
+        for profile in ["", "../work", "work/other", "work space"] {
+            assert!(matches!(
+                Credentials::key(profile),
+                Err(Error::InvalidProfile(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn the_stored_profile_defaults_when_missing_or_blank() {
+        let storage = MemoryStorage::default();
+        assert_eq!(stored_profile(&storage).unwrap(), DEFAULT_PROFILE);
+
+        storage.write("profile", b"  \n", false).unwrap();
+        assert_eq!(stored_profile(&storage).unwrap(), DEFAULT_PROFILE);
+
+        storage.write("profile", b"work\n", false).unwrap();
+        assert_eq!(stored_profile(&storage).unwrap(), "work");
     }
 }
@@ -0,0 +1,49 @@
+//! Typed access to deployments, for use outside of the CLI.
+
+use anyhow::Result;
+
+use crate::{
+    api::ApiClient,
+    command::deployments::{delete, describe, list},
+};
+
+pub use crate::command::deployments::{
+    describe::describe_deployment::DescribeDeploymentDeployment as Deployment, list::Entry,
+    services::Service,
+};
+
+/// Deployment operations authenticated as the active `slot` profile.
+pub struct Deployments {
+    client: ApiClient,
+}
+
+impl Deployments {
+    pub fn new() -> Self {
+        Self::with_client(ApiClient::new())
+    }
+
+    pub fn with_client(client: ApiClient) -> Self {
+        Self { client }
+    }
+
+    /// Every deployment of the teams the user belongs to, optionally limited to one team.
+    pub async fn list(&self, team: Option<&str>) -> Result<Vec<Entry>> {
+        list::fetch(&self.client, team, None).await
+    }
+
+    /// The configuration and endpoints of a single service of a project.
+    pub async fn describe(&self, project: &str, service: &Service) -> Result<Deployment> {
+        describe::fetch(&self.client, project, service).await
+    }
+
+    /// Permanently delete a service of a project.
+    pub async fn delete(&self, project: &str, service: &Service) -> Result<()> {
+        delete::delete(&self.client, project, service).await
+    }
+}
+
+impl Default for Deployments {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,35 @@
+use std::{io, time::Duration};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("No credentials found. Login with `slot auth login`.")]
+    CredentialsNotFound,
+    #[error("Invalid token, authenticate with `slot auth login`.")]
+    CredentialsExpired,
+    #[error("Malformed credentials file: {0}")]
+    MalformedCredentials(#[source] serde_json::Error),
+    #[error("OS keychain error: {0}")]
+    Keyring(#[from] keyring::Error),
+    #[error("Invalid profile name `{0}`. Use letters, digits, `-` and `_` only.")]
+    InvalidProfile(String),
+    #[error("Malformed config file: {0}")]
+    MalformedConfig(#[source] toml::de::Error),
+    #[error("Unknown config key `{0}`.")]
+    UnknownConfigKey(String),
+    #[error("Invalid value `{1}` for config key `{0}`.")]
+    InvalidConfigValue(String, String),
+    #[error("Unable to locate the local config directory.")]
+    ConfigDirNotFound,
+    #[error("Unable to locate a directory for credentials. Set SLOT_STATE_DIR.")]
+    StateDirNotFound,
+    #[error("Callback server failed: {0}")]
+    CallbackServerFailed(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Timed out after {}s waiting for the browser callback.", .0.as_secs())]
+    CallbackTimedOut(Duration),
+    #[error("Interrupted while waiting for the browser callback.")]
+    Interrupted,
+    #[error("Login failed: {0}")]
+    LoginFailed(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
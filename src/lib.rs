@@ -0,0 +1,40 @@
+//! The Slot CLI as a library, for tools that manage deployments without shelling out to
+//! `slot`.
+//!
+//! Requests are authenticated with the credentials stored by `slot auth login`, or with
+//! `SLOT_AUTH_TOKEN` when it is set, and honor the same config file and environment
+//! variables as the CLI.
+//!
+//! ```no_run
+//! # async fn example() -> anyhow::Result<()> {
+//! use slot::deployments::{Deployments, Service};
+//!
+//! let deployments = Deployments::new();
+//! for deployment in deployments.list(None).await? {
+//!     println!("{} {}", deployment.project, deployment.service);
+//! }
+//! deployments.delete("my-project", &Service::Torii).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Commands without a dedicated method can be run by building their arguments from
+//! [`command`], e.g. [`command::deployments::Deployments::Create`].
+
+pub mod api;
+pub mod cli;
+pub mod command;
+pub mod config;
+pub mod constant;
+pub mod credential;
+pub mod deployments;
+pub mod error;
+pub mod logging;
+pub mod output;
+
+mod browser;
+mod cache;
+mod project;
+mod prompt;
+mod server;
+mod storage;
@@ -0,0 +1,64 @@
+use std::{
+    fs::OpenOptions,
+    io::{self, IsTerminal},
+    path::Path,
+    sync::Mutex,
+};
+
+use anyhow::Result;
+use clap::ValueEnum;
+use tracing_subscriber::{
+    filter::EnvFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt, Layer, Registry,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Log errors to stderr, or more with `--verbose` once (info), twice (debug) or three times
+/// (trace).
+/// Without `-v`, `RUST_LOG` is honored. A `--log-file` always gets debug logs as well, so a
+/// failed run can be looked into afterwards without rerunning it.
+pub fn init(verbose: u8, format: LogFormat, file: Option<&Path>) -> Result<()> {
+    let level = match verbose {
+        0 => None,
+        1 => Some("slot=info"),
+        2 => Some("slot=debug"),
+        _ => Some("slot=trace"),
+    };
+    let filter = || match level {
+        Some(level) => EnvFilter::new(level),
+        None => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("error")),
+    };
+
+    let stderr = fmt::layer()
+        .with_ansi(io::stderr().is_terminal())
+        .with_writer(io::stderr);
+    let mut layers = vec![layer(format, stderr).with_filter(filter()).boxed()];
+
+    if let Some(path) = file {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let writer = fmt::layer().with_ansi(false).with_writer(Mutex::new(file));
+        let filter = filter().add_directive("slot=debug".parse()?);
+        layers.push(layer(format, writer).with_filter(filter).boxed());
+    }
+
+    tracing_subscriber::registry().with(layers).try_init()?;
+    Ok(())
+}
+
+fn layer<W>(
+    format: LogFormat,
+    layer: fmt::Layer<Registry, fmt::format::DefaultFields, fmt::format::Format, W>,
+) -> Box<dyn Layer<Registry> + Send + Sync>
+where
+    W: for<'w> fmt::MakeWriter<'w> + Send + Sync + 'static,
+{
+    match format {
+        LogFormat::Text => layer.boxed(),
+        LogFormat::Json => layer.json().boxed(),
+    }
+}
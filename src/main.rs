@@ -1,24 +1,39 @@
-mod api;
-mod browser;
-mod cli;
-mod command;
-mod constant;
-mod credential;
-mod server;
-
 use clap::Parser;
-use cli::Cli;
-use log::error;
+use slot::{
+    api,
+    cli::Cli,
+    command::{ci, Command},
+    config, logging, output,
+};
+use tracing::{error, warn};
 
 #[tokio::main]
 async fn main() {
-    env_logger::init();
     let cli = Cli::parse();
 
+    if let Err(e) = logging::init(cli.verbose, cli.log_format, cli.log_file.as_deref()) {
+        eprintln!("Error: failed to set up logging: {e}");
+        std::process::exit(1);
+    }
+
+    if let Err(e) = config::Config::init() {
+        // `config` and `doctor` are how a broken config gets fixed, so let them run on defaults.
+        if !matches!(cli.command, Command::Config(_) | Command::Doctor(_)) {
+            error!("{e}");
+            std::process::exit(1);
+        }
+        warn!("{e}");
+    }
+    output::init(cli.output);
+    api::init_retries(cli.retries, cli.retry_max_delay);
+    api::init_concurrency(cli.concurrency);
+
     match &cli.command.run().await {
         Ok(_) => {}
         Err(e) => {
-            error!("{e}")
+            error!("{e}");
+            ci::annotate_error(&e.to_string());
+            std::process::exit(1);
         }
     }
 }
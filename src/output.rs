@@ -0,0 +1,57 @@
+use std::sync::OnceLock;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::config::Config;
+
+static OUTPUT: OnceLock<Output> = OnceLock::new();
+
+/// How commands print their results. Text is meant for people and may change between
+/// releases; JSON is meant for scripts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Output {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Pick the output format for this run: the `--output` flag, then the `output` setting.
+pub fn init(flag: Option<Output>) {
+    let output = flag.unwrap_or_else(|| match Config::get().output.as_deref() {
+        Some("json") => Output::Json,
+        _ => Output::Text,
+    });
+    let _ = OUTPUT.set(output);
+}
+
+pub fn is_json() -> bool {
+    OUTPUT.get().copied().unwrap_or_default() == Output::Json
+}
+
+/// Scripts reading JSON can't answer a confirmation prompt, so under JSON output the flag
+/// that skips it is required.
+pub fn require_confirmed(confirmed: bool, flag: &str) -> anyhow::Result<()> {
+    if is_json() && !confirmed {
+        return Err(anyhow::anyhow!(
+            "`--output json` can't ask for confirmation, pass `{flag}`."
+        ));
+    }
+    Ok(())
+}
+
+/// For commands that only work on a terminal: fail rather than print text where JSON was
+/// asked for.
+pub fn text_only(command: &str) -> anyhow::Result<()> {
+    if is_json() {
+        return Err(anyhow::anyhow!(
+            "`{command}` is interactive and has no JSON output."
+        ));
+    }
+    Ok(())
+}
+
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! A `.slot/project.toml` binds a directory, e.g. a Dojo project, to a deployment, so
+//! commands run inside it can leave out the project and service:
+//!
+//! ```toml
+//! team = "my-team"
+//! project = "my-game"
+//! service = "torii"
+//! ```
+
+use std::{env, fs, path::PathBuf};
+
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::command::deployments::services::Service;
+
+const FILE: &str = ".slot/project.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ProjectConfig {
+    pub team: Option<String>,
+    pub project: Option<String>,
+    pub service: Option<String>,
+}
+
+impl ProjectConfig {
+    /// The nearest `.slot/project.toml` in the current directory or one of its parents, or an
+    /// empty config if there is none.
+    pub(crate) fn load() -> Result<Self> {
+        let Some(path) = Self::find() else {
+            return Ok(Self::default());
+        };
+
+        let contents = fs::read_to_string(&path)?;
+        toml::from_str(&contents).map_err(|e| anyhow!("Invalid {}: {e}", path.display()))
+    }
+
+    fn find() -> Option<PathBuf> {
+        env::current_dir()
+            .ok()?
+            .ancestors()
+            .map(|dir| dir.join(FILE))
+            .find(|path| path.is_file())
+    }
+}
+
+/// The deployment a command targets: what was passed on the command line, else what the
+/// project file pins.
+pub(crate) fn deployment(
+    project: &Option<String>,
+    service: &Option<Service>,
+) -> Result<(String, Service)> {
+    if let (Some(project), Some(service)) = (project, service) {
+        return Ok((project.clone(), service.clone()));
+    }
+
+    resolve(project, service, ProjectConfig::load()?)
+}
+
+/// Fill in whatever wasn't passed from `config`.
+fn resolve(
+    project: &Option<String>,
+    service: &Option<Service>,
+    config: ProjectConfig,
+) -> Result<(String, Service)> {
+    let project = project
+        .clone()
+        .or(config.project)
+        .ok_or_else(|| anyhow!("Missing the project. Pass it or set `project` in {FILE}."))?;
+    let service = match (service, config.service) {
+        (Some(service), _) => service.clone(),
+        (None, Some(service)) => Service::from_str(&service, true)
+            .map_err(|_| anyhow!("Unknown service `{service}` in {FILE}."))?,
+        (None, None) => {
+            return Err(anyhow!(
+                "Missing the service. Pass it or set `service` in {FILE}."
+            ))
+        }
+    };
+
+    Ok((project, service))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pinned(project: &str, service: &str) -> ProjectConfig {
+        ProjectConfig {
+            team: None,
+            project: Some(project.to_string()),
+            service: Some(service.to_string()),
+        }
+    }
+
+    #[test]
+    fn arguments_take_precedence_over_the_project_file() {
+        let (project, service) = resolve(
+            &Some("passed".to_string()),
+            &Some(Service::Katana),
+            pinned("pinned", "torii"),
+        )
+        .unwrap();
+
+        assert_eq!(project, "passed");
+        assert!(matches!(service, Service::Katana));
+    }
+
+    #[test]
+    fn missing_arguments_come_from_the_project_file() {
+        let (project, service) = resolve(&None, &None, pinned("pinned", "Torii")).unwrap();
+        assert_eq!(project, "pinned");
+        assert!(matches!(service, Service::Torii));
+
+        let (project, service) =
+            resolve(&None, &Some(Service::Katana), pinned("pinned", "torii")).unwrap();
+        assert_eq!(project, "pinned");
+        assert!(matches!(service, Service::Katana));
+    }
+
+    #[test]
+    fn unresolvable_deployments_say_what_is_missing() {
+        let err = resolve(&None, &Some(Service::Katana), ProjectConfig::default()).unwrap_err();
+        assert!(err.to_string().starts_with("Missing the project."), "{err}");
+
+        let err = resolve(&Some("p".to_string()), &None, ProjectConfig::default()).unwrap_err();
+        assert!(err.to_string().starts_with("Missing the service."), "{err}");
+
+        let err = resolve(&None, &None, pinned("pinned", "madara")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("Unknown service `madara` in {FILE}.")
+        );
+    }
+
+    #[test]
+    fn project_files_reject_unknown_keys() {
+        assert!(toml::from_str::<ProjectConfig>("project = \"p\"\nservise = \"torii\"").is_err());
+    }
+}
@@ -0,0 +1,18 @@
+use std::io::{self, Write};
+
+/// Ask a yes/no question on the terminal. Anything but `y`/`yes` counts as no.
+pub fn confirm(message: &str) -> io::Result<bool> {
+    let answer = input(&format!("{message} [y/N]"))?;
+    Ok(matches!(answer.to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Print `message` and read one trimmed line from stdin.
+pub fn input(message: &str) -> io::Result<String> {
+    print!("{message} ");
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    Ok(answer.trim().to_string())
+}
@@ -2,33 +2,42 @@ use anyhow::Result;
 use axum::{
     extract::{Query, State},
     http::StatusCode,
-    response::{IntoResponse, Redirect, Response},
+    response::{Html, IntoResponse, Response},
     routing::get,
     Router,
 };
-use log::error;
+use rand::{distributions::Alphanumeric, Rng};
 use serde::Deserialize;
 use std::{
     net::{SocketAddr, TcpListener},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 use tokio::sync::mpsc::{Receiver, Sender};
 
-use crate::{constant, credential::Credentials};
+use crate::{constant, credential::Credentials, error::Error};
 
 pub struct LocalServer {
     router: Router,
-    shutdown_rx: Receiver<()>,
+    shutdown_rx: Receiver<Result<(), String>>,
     listener: TcpListener,
+    state: String,
 }
 
-impl<'a> LocalServer {
-    pub fn new() -> Result<Self> {
-        let (tx, rx) = tokio::sync::mpsc::channel::<()>(1);
+impl LocalServer {
+    /// Serve the login callback, storing the credentials it receives under `profile`.
+    pub fn new(host: &str, port: u16, profile: String) -> Result<Self, Error> {
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
         // Port number of 0 requests OS to find an available port.
-        let listener = TcpListener::bind("localhost:0")?;
+        let listener = TcpListener::bind((host, port))?;
 
-        let shared_state = Arc::new(AppState::new(tx));
+        // One-time token the callback must echo back, so other local pages can't forge it.
+        let state = random_state();
+
+        let shared_state = Arc::new(AppState::new(tx, state.clone(), profile));
         let router = Router::new()
             .route("/callback", get(Self::callback))
             .with_state(shared_state);
@@ -37,104 +46,207 @@ impl<'a> LocalServer {
             router,
             shutdown_rx: rx,
             listener,
+            state,
         })
     }
 
+    pub fn state(&self) -> &str {
+        &self.state
+    }
+
     pub fn local_addr(&self) -> Result<SocketAddr, std::io::Error> {
         self.listener.local_addr()
     }
 
-    pub async fn start(mut self) -> Result<()> {
-        axum::Server::from_tcp(self.listener)?
+    /// Serve until the callback is handled, `timeout` elapses or the user hits Ctrl-C, then
+    /// stop and free the port. Fails if the login didn't go through.
+    pub async fn start(mut self, timeout: Duration) -> Result<(), Error> {
+        let mut outcome = Ok(());
+
+        axum::Server::from_tcp(self.listener)
+            .map_err(|e| Error::CallbackServerFailed(e.into()))?
             .serve(self.router.into_make_service())
             .with_graceful_shutdown(async {
-                let _ = &self.shutdown_rx.recv().await;
+                tokio::select! {
+                    result = self.shutdown_rx.recv() => {
+                        outcome = result.unwrap_or(Ok(())).map_err(Error::LoginFailed);
+                    }
+                    _ = tokio::time::sleep(timeout) => {
+                        outcome = Err(Error::CallbackTimedOut(timeout));
+                    }
+                    _ = tokio::signal::ctrl_c() => {
+                        outcome = Err(Error::Interrupted);
+                    }
+                }
             })
-            .await?;
+            .await
+            .map_err(|e| Error::CallbackServerFailed(e.into()))?;
 
-        Ok(())
+        outcome
     }
 
+    /// Handles the first callback carrying the right state; any later one is turned away.
     async fn callback(
         State(state): State<Arc<AppState>>,
         Query(payload): Query<CallbackPayload>,
-    ) -> Result<Redirect, AppError> {
-        // 1. Shutdown the server
-        state.shutdown().await?;
-
-        // 2. Get access token using the authorization code
-        match payload.code {
-            Some(code) => {
-                let client = reqwest::Client::new();
-                let response = client
-                    .post(format!("{}oauth2/token", constant::CARTRIDGE_API_URL))
-                    .form(&[("code", &code)])
-                    .send()
-                    .await?;
-
-                let cred: Credentials = response.json().await?;
-
-                // 3. Store the access token locally
-                cred.write()?;
-
-                println!("You are now logged in!\n");
-
-                Ok(Redirect::permanent(&format!(
-                    "{}/slot/auth/success",
-                    constant::CARTRIDGE_KEYCHAIN_URL
-                )))
-            }
-            None => {
-                error!("User denied consent. Try again.");
-
-                Ok(Redirect::permanent(&format!(
-                    "{}/slot/auth/failure",
-                    constant::CARTRIDGE_KEYCHAIN_URL
-                )))
-            }
+    ) -> Response {
+        if payload.state.as_deref() != Some(state.state.as_str()) {
+            return Page::InvalidState.into_response();
         }
+        if state.handled.swap(true, Ordering::SeqCst) {
+            return Page::AlreadyUsed.into_response();
+        }
+
+        let (outcome, page) = match payload.code {
+            Some(code) => match login(&code, &state.profile).await {
+                Ok(()) => (Ok(()), Page::LoggedIn),
+                Err(e) => (Err(e.to_string()), Page::Failed(e.to_string())),
+            },
+            None => (
+                Err("Consent was denied. Try again.".to_string()),
+                Page::Denied,
+            ),
+        };
+
+        // The response still goes out: shutdown waits for requests in flight.
+        state.shutdown(outcome).await;
+        page.into_response()
     }
 }
 
-#[derive(Deserialize)]
-struct CallbackPayload {
-    code: Option<String>,
+/// Exchange the authorization code for credentials and store them under `profile`.
+async fn login(code: &str, profile: &str) -> Result<()> {
+    exchange_code(code).await?.write_profile(profile)?;
+    Ok(())
 }
 
-#[derive(Clone)]
-struct AppState {
-    shutdown_tx: Sender<()>,
+/// What the browser shows once it's sent back from the keychain.
+enum Page {
+    LoggedIn,
+    Denied,
+    Failed(String),
+    InvalidState,
+    AlreadyUsed,
 }
 
-impl AppState {
-    fn new(shutdown_tx: Sender<()>) -> Self {
-        Self { shutdown_tx }
+impl IntoResponse for Page {
+    fn into_response(self) -> Response {
+        let (status, title, message, hint) = match &self {
+            Page::LoggedIn => (
+                StatusCode::OK,
+                "You're logged in",
+                "The Slot CLI received your credentials.".to_string(),
+                "You can close this tab and return to your terminal.",
+            ),
+            Page::Denied => (
+                StatusCode::BAD_REQUEST,
+                "Login cancelled",
+                "The request wasn't approved, so the Slot CLI wasn't logged in.".to_string(),
+                "Run <code>slot auth login</code> again and approve the request.",
+            ),
+            Page::Failed(reason) => (
+                StatusCode::BAD_GATEWAY,
+                "Login failed",
+                format!(
+                    "The Slot CLI couldn't complete the login: {}",
+                    escape(reason)
+                ),
+                "Run <code>slot doctor</code> to check your setup, then \
+                 <code>slot auth login</code> again.",
+            ),
+            Page::InvalidState => (
+                StatusCode::FORBIDDEN,
+                "Unknown login",
+                "This link doesn't belong to the login your terminal is waiting for.".to_string(),
+                "Use the link printed by the latest <code>slot auth login</code>.",
+            ),
+            Page::AlreadyUsed => (
+                StatusCode::GONE,
+                "Already handled",
+                "This login was already completed.".to_string(),
+                "Your terminal has the result. You can close this tab.",
+            ),
+        };
+        let (icon, color) = match self {
+            Page::LoggedIn | Page::AlreadyUsed => ("\\2713", "#52c46e"),
+            _ => ("\\2715", "#e5534b"),
+        };
+
+        let html = include_str!("server/callback.html")
+            .replace("{title}", title)
+            .replace("{icon}", icon)
+            .replace("{color}", color)
+            .replace("{hint}", hint)
+            .replace(
+                "{instructions}",
+                &format!("https://github.com/{}#usage", constant::REPO),
+            )
+            // Last, since the reason in it comes from elsewhere and could hold a placeholder.
+            .replace("{message}", &message);
+        (status, Html(html)).into_response()
     }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
 
-    async fn shutdown(&self) -> Result<()> {
-        self.shutdown_tx.send(()).await?;
+pub fn random_state() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
 
-        Ok(())
+/// Exchange an authorization code for an access token.
+pub async fn exchange_code(code: &str) -> Result<Credentials> {
+    let client = reqwest::Client::new();
+    let response = client
+        .post(format!("{}/oauth2/token", constant::api_url()))
+        .form(&[("code", code)])
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!(
+            "the authorization code was rejected ({})",
+            response.status()
+        ));
     }
+
+    Ok(response.json().await?)
+}
+
+#[derive(Deserialize)]
+struct CallbackPayload {
+    code: Option<String>,
+    state: Option<String>,
 }
 
-struct AppError(anyhow::Error);
+struct AppState {
+    shutdown_tx: Sender<Result<(), String>>,
+    state: String,
+    profile: String,
+    handled: AtomicBool,
+}
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Something went wrong: {}", self.0),
-        )
-            .into_response()
+impl AppState {
+    fn new(shutdown_tx: Sender<Result<(), String>>, state: String, profile: String) -> Self {
+        Self {
+            shutdown_tx,
+            state,
+            profile,
+            handled: AtomicBool::new(false),
+        }
     }
-}
 
-impl<E> From<E> for AppError
-where
-    E: Into<anyhow::Error>,
-{
-    fn from(err: E) -> Self {
-        Self(err.into())
+    /// Stop the server, reporting how the login went.
+    async fn shutdown(&self, outcome: Result<(), String>) {
+        // The channel only closes once the server is already shutting down.
+        let _ = self.shutdown_tx.send(outcome).await;
     }
 }
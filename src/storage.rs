@@ -0,0 +1,311 @@
+//! Where the CLI keeps its files. Credentials and the active profile go through [`Storage`],
+//! so their logic can be exercised against [`MemoryStorage`] without touching the disk.
+
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use rand::{distributions::Alphanumeric, Rng};
+
+use crate::{config::state_dir, error::Error};
+
+/// Files addressed by `/`-separated keys relative to the storage root, e.g.
+/// `profiles/work/credentials.json`.
+pub(crate) trait Storage {
+    /// The contents of `key`, or `None` if it doesn't exist.
+    fn read(&self, key: &str) -> io::Result<Option<Vec<u8>>>;
+
+    /// Replace `key` with `contents`. `private` files are only readable by their owner.
+    fn write(&self, key: &str, contents: &[u8], private: bool) -> io::Result<()>;
+
+    /// Remove `key`. Removing a key that doesn't exist is not an error.
+    fn remove(&self, key: &str) -> io::Result<()>;
+}
+
+/// Files under a directory on disk, written atomically.
+pub(crate) struct FileStorage {
+    root: PathBuf,
+}
+
+impl FileStorage {
+    pub(crate) fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Storage under the state directory, which holds credentials and the active profile.
+    pub(crate) fn state() -> Result<Self, Error> {
+        Ok(Self::new(state_dir()?))
+    }
+
+    pub(crate) fn path(&self, key: &str) -> PathBuf {
+        key.split('/')
+            .fold(self.root.clone(), |path, part| path.join(part))
+    }
+}
+
+impl Storage for FileStorage {
+    fn read(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(self.path(key)) {
+            Ok(contents) => Ok(Some(contents)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn write(&self, key: &str, contents: &[u8], private: bool) -> io::Result<()> {
+        write_atomic(&self.path(key), contents, private)
+    }
+
+    fn remove(&self, key: &str) -> io::Result<()> {
+        match fs::remove_file(self.path(key)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Replace `path` with `contents` by writing a temporary file next to it, syncing it to disk
+/// and renaming it over the original, so readers never see a truncated file.
+pub(crate) fn write_atomic(path: &Path, contents: &[u8], private: bool) -> io::Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(dir)?;
+
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = dir.join(format!(".{name}.{}.tmp", random_name(8)));
+
+    let result = (|| {
+        let mut file = options(private).open(&tmp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result?;
+
+    // The rename itself only lasts once the directory entry is on disk.
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+
+    Ok(())
+}
+
+/// A private file with a unique name in the system temp dir, removed when dropped.
+pub(crate) struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// Create `slot-<random><suffix>` holding `contents`. The name can't be guessed and the
+    /// file is created exclusively, so nobody else sharing the temp dir can plant it first.
+    pub(crate) fn new(suffix: &str, contents: &[u8]) -> io::Result<Self> {
+        let path = env::temp_dir().join(format!("slot-{}{suffix}", random_name(12)));
+        let mut file = options(true).open(&path)?;
+        let temp = TempFile { path };
+        file.write_all(contents)?;
+        Ok(temp)
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn random_name(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+fn options(private: bool) -> OpenOptions {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+    options
+}
+
+#[cfg(test)]
+pub(crate) use self::memory::MemoryStorage;
+
+#[cfg(test)]
+mod memory {
+    use std::{collections::HashMap, io, sync::Mutex};
+
+    use super::Storage;
+
+    /// Files kept in a map, along with whether they were written as private.
+    #[derive(Default)]
+    pub(crate) struct MemoryStorage {
+        files: Mutex<HashMap<String, (Vec<u8>, bool)>>,
+    }
+
+    impl MemoryStorage {
+        pub(crate) fn keys(&self) -> Vec<String> {
+            let mut keys: Vec<String> = self.files.lock().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        }
+
+        pub(crate) fn is_private(&self, key: &str) -> bool {
+            self.files
+                .lock()
+                .unwrap()
+                .get(key)
+                .is_some_and(|(_, private)| *private)
+        }
+    }
+
+    impl Storage for MemoryStorage {
+        fn read(&self, key: &str) -> io::Result<Option<Vec<u8>>> {
+            Ok(self.files.lock().unwrap().get(key).map(|(c, _)| c.clone()))
+        }
+
+        fn write(&self, key: &str, contents: &[u8], private: bool) -> io::Result<()> {
+            let file = (contents.to_vec(), private);
+            self.files.lock().unwrap().insert(key.to_string(), file);
+            Ok(())
+        }
+
+        fn remove(&self, key: &str) -> io::Result<()> {
+            self.files.lock().unwrap().remove(key);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+
+    /// A fresh directory under the system temp dir, removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let name = random_name(12);
+            Self(env::temp_dir().join(format!("slot-storage-{name}")))
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn write_creates_the_parent_directories_not_the_file_as_a_directory() {
+        let dir = TempDir::new();
+        let storage = FileStorage::new(&dir.0);
+
+        storage
+            .write("profiles/work/credentials.json", b"{}", false)
+            .unwrap();
+
+        let path = dir.0.join("profiles").join("work").join("credentials.json");
+        assert!(path.is_file());
+        assert_eq!(fs::read(path).unwrap(), b"{}");
+    }
+
+    #[test]
+    fn write_replaces_the_file_and_leaves_no_temporary_files() {
+        let dir = TempDir::new();
+        let storage = FileStorage::new(&dir.0);
+
+        storage.write("profile", b"first", false).unwrap();
+        storage.write("profile", b"second", false).unwrap();
+
+        assert_eq!(storage.read("profile").unwrap().unwrap(), b"second");
+        let entries: Vec<_> = fs::read_dir(&dir.0).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn private_files_are_only_readable_by_their_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new();
+        let storage = FileStorage::new(&dir.0);
+
+        storage.write("credentials.json", b"{}", true).unwrap();
+
+        let mode = fs::metadata(storage.path("credentials.json"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn missing_files_read_as_none_and_remove_cleanly() {
+        let dir = TempDir::new();
+        let storage = FileStorage::new(&dir.0);
+
+        assert!(storage.read("credentials.json").unwrap().is_none());
+        storage.remove("credentials.json").unwrap();
+    }
+
+    #[test]
+    fn remove_deletes_the_file() {
+        let dir = TempDir::new();
+        let storage = FileStorage::new(&dir.0);
+
+        storage.write("profile", b"work", false).unwrap();
+        storage.remove("profile").unwrap();
+
+        assert!(storage.read("profile").unwrap().is_none());
+    }
+
+    #[test]
+    fn temp_files_get_unique_names_and_are_removed_when_dropped() {
+        let one = TempFile::new(".toml", b"a = 1").unwrap();
+        let two = TempFile::new(".toml", b"a = 1").unwrap();
+        assert_ne!(one.path(), two.path());
+        assert_eq!(fs::read(one.path()).unwrap(), b"a = 1");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(one.path()).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let path = one.path().to_path_buf();
+        drop(one);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn memory_storage_round_trips() {
+        let storage = MemoryStorage::default();
+
+        storage.write("credentials.json", b"{}", true).unwrap();
+        assert_eq!(storage.read("credentials.json").unwrap().unwrap(), b"{}");
+        assert!(storage.is_private("credentials.json"));
+
+        storage.remove("credentials.json").unwrap();
+        assert!(storage.read("credentials.json").unwrap().is_none());
+        assert!(storage.keys().is_empty());
+    }
+}
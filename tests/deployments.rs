@@ -0,0 +1,141 @@
+//! The deployments API against canned responses, checking what is sent and how replies are read.
+
+use serde_json::json;
+use slot::{
+    api::{ApiClient, ApiError, MockApi},
+    deployments::{Deployments, Service},
+};
+
+fn deployments(api: &MockApi) -> Deployments {
+    Deployments::with_client(ApiClient::with_api(api.clone()))
+}
+
+fn page(projects: &[&str], next: Option<&str>) -> serde_json::Value {
+    let edges: Vec<_> = projects
+        .iter()
+        .map(|project| {
+            json!({ "node": {
+                "project": project,
+                "branch": null,
+                "createdAt": "2024-06-01T00:00:00Z",
+                "service": { "id": "katana" },
+            }})
+        })
+        .collect();
+
+    json!({
+        "pageInfo": { "hasNextPage": next.is_some(), "endCursor": next },
+        "edges": edges,
+    })
+}
+
+#[tokio::test]
+async fn list_follows_every_page_of_each_team() {
+    let api = MockApi::new();
+    api.respond(
+        "ListDeployments",
+        json!({ "me": { "id": "me", "name": "me", "teams": { "edges": [
+            { "node": { "id": "t1", "name": "team", "deployments": page(&["a"], Some("c1")) } },
+        ]}}}),
+    );
+    api.respond(
+        "TeamDeployments",
+        json!({ "node": { "__typename": "Team", "deployments": page(&["b"], None) } }),
+    );
+
+    let entries = deployments(&api).list(None).await.unwrap();
+
+    let projects: Vec<_> = entries.iter().map(|e| e.project.as_str()).collect();
+    assert_eq!(projects, ["a", "b"]);
+    assert!(entries
+        .iter()
+        .all(|e| e.team == "team" && e.service == "katana"));
+
+    let requests = api.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1].body["variables"]["team"], "t1");
+    assert_eq!(requests[1].body["variables"]["after"], "c1");
+}
+
+#[tokio::test]
+async fn list_can_be_limited_to_one_team() {
+    let api = MockApi::new();
+    api.respond(
+        "ListDeployments",
+        json!({ "me": { "id": "me", "name": "me", "teams": { "edges": [
+            { "node": { "id": "t1", "name": "one", "deployments": page(&["a"], None) } },
+            { "node": { "id": "t2", "name": "two", "deployments": page(&["b"], None) } },
+        ]}}}),
+    );
+
+    let entries = deployments(&api).list(Some("two")).await.unwrap();
+
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].project, "b");
+}
+
+#[tokio::test]
+async fn describing_a_missing_deployment_is_an_error() {
+    let api = MockApi::new();
+    api.respond("DescribeDeployment", json!({ "deployment": null }));
+
+    let error = deployments(&api)
+        .describe("ghost", &Service::Katana)
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.to_string(), "Deployment ghost not found.");
+    let variables = &api.requests()[0].body["variables"];
+    assert_eq!(variables["project"], "ghost");
+    assert_eq!(variables["service"], "katana");
+}
+
+#[tokio::test]
+async fn mutations_carry_an_idempotency_key_and_queries_do_not() {
+    let api = MockApi::new();
+    api.respond("DeleteDeployment", json!({ "deleteDeployment": true }));
+    api.respond("DescribeDeployment", json!({ "deployment": null }));
+
+    deployments(&api)
+        .delete("my-project", &Service::Torii)
+        .await
+        .unwrap();
+    let _ = deployments(&api)
+        .describe("my-project", &Service::Torii)
+        .await;
+
+    let requests = api.requests();
+    assert!(requests[0].idempotency_key.is_some());
+    assert!(requests[1].idempotency_key.is_none());
+}
+
+#[tokio::test]
+async fn graphql_errors_keep_their_code() {
+    let api = MockApi::new();
+    api.respond_error(
+        "DeleteDeployment",
+        "deployment not found",
+        Some("NOT_FOUND"),
+    );
+
+    let error = deployments(&api)
+        .delete("ghost", &Service::Katana)
+        .await
+        .unwrap_err();
+
+    let Some(ApiError::Graphql(errors)) = error.downcast_ref::<ApiError>() else {
+        panic!("expected a GraphQL error, got {error}");
+    };
+    assert_eq!(errors[0].message, "deployment not found");
+    assert_eq!(errors[0].code.as_deref(), Some("NOT_FOUND"));
+}
+
+#[tokio::test]
+async fn operations_without_a_response_fail() {
+    let api = MockApi::new();
+
+    let result = deployments(&api).list(None).await;
+
+    assert!(result.is_err());
+    assert_eq!(api.requests()[0].operation_name, "ListDeployments");
+}